@@ -1,10 +1,19 @@
+use crate::bitset::BitSet;
 use crate::error::AliquotError;
-use crate::types::Number;
-use std::collections::{HashMap, HashSet};
+use crate::sieve::Sieve;
+use crate::stats::StatsHandle;
+use crate::types::{Number, iter_range};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::fmt;
 use std::ops::Range;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
 
 /// Possible aliquot sequences defined in an enum.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AliquotSeq<T: Number> {
     PerfectNumber(T),
     PrimeNumber((T, T)),
@@ -13,7 +22,72 @@ pub enum AliquotSeq<T: Number> {
     SociableNumber(Vec<T>),
     AspiringNumber(Vec<T>),
     IntoCycle(Vec<T>, Vec<T>),
-    Unknown(Vec<T>, String),
+    Unknown(Vec<T>, UnknownMessage),
+}
+
+/// The terminal fate of an aliquot sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Fate {
+    /// The sequence terminates at one.
+    Terminates,
+    /// The sequence ends in a perfect number.
+    Perfect,
+    /// The sequence is or runs into a cycle of amicable or sociable numbers.
+    Cycle,
+    /// The fate of the sequence is unknown.
+    Open,
+}
+
+/// Classification of a number by comparing its aliquot sum with the number itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NumberClass {
+    /// The aliquot sum is smaller than the number.
+    Deficient,
+    /// The aliquot sum equals the number.
+    Perfect,
+    /// The aliquot sum is greater than the number.
+    Abundant,
+}
+
+/// The limit or error, which stopped the computation of an unknown sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnknownReason {
+    /// The sequence exceeded the maximum length.
+    MaxLength,
+    /// A number of the sequence exceeded the maximum value.
+    MaxValue,
+    /// An error like an overflow occurred computing the next number.
+    Arithmetic,
+    /// The computation was interrupted.
+    Interrupted,
+    /// The sequence of zero and one is undefined.
+    Undefined,
+}
+
+/// Message of an unknown sequence describing the limit or error, which stopped
+/// its computation, like "Maximum value 400 exceeded".
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownMessage {
+    pub reason: UnknownReason,
+    pub text: String,
+}
+
+impl UnknownMessage {
+    /// Returns a new message for the reason described by text.
+    pub fn new(reason: UnknownReason, text: impl Into<String>) -> Self {
+        Self {
+            reason,
+            text: text.into(),
+        }
+    }
+}
+
+impl fmt::Display for UnknownMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
 }
 
 impl<T: Number> AliquotSeq<T> {
@@ -46,6 +120,11 @@ impl<T: Number> AliquotSeq<T> {
         }
     }
 
+    /// Returns false, since an aliquot sequence always contains at least its starting number.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
     /// Returns the type of the aliquot sequence as a string.
     pub fn type_str(&self) -> String {
         match self {
@@ -56,7 +135,22 @@ impl<T: Number> AliquotSeq<T> {
             AliquotSeq::SociableNumber(_) => "Sociable number".to_string(),
             AliquotSeq::AspiringNumber(_) => "Aspiring number".to_string(),
             AliquotSeq::IntoCycle(_, _) => "Convergent into cycle".to_string(),
-            AliquotSeq::Unknown(_, reason) => format!("Unknown sequence (Reason: {reason})"),
+            AliquotSeq::Unknown(_, message) => format!("Unknown sequence (Reason: {message})"),
+        }
+    }
+
+    /// Returns true, if the sequence ends with a perfect number.
+    pub fn is_aspiring(&self) -> bool {
+        matches!(self, AliquotSeq::AspiringNumber(_))
+    }
+
+    /// Returns true, if kind is the type string of the sequence as returned by
+    /// type_str. Unknown sequences also match "Unknown sequence" without the
+    /// reason.
+    pub fn matches_type(&self, kind: &str) -> bool {
+        match self {
+            AliquotSeq::Unknown(_, _) if kind == "Unknown sequence" => true,
+            _ => self.type_str() == kind,
         }
     }
 
@@ -80,24 +174,27 @@ impl<T: Number> AliquotSeq<T> {
 
     /// Returns the sequence as a string.
     pub fn seq_string(&self) -> String {
+        self.seq_string_with(|t| t.to_string())
+    }
+
+    /// Returns the sequence as a string using the given function to format the terms.
+    pub fn seq_string_with(&self, fmt: impl Fn(T) -> String) -> String {
         let vec_to_string = |v: &Vec<T>| -> String {
-            let mut ret = format!("[{}", v[0]);
-            for val in v.iter().skip(1) {
-                ret += format!(", {val}").as_str();
+            let mut ret = format!("[{}", fmt(v[0]));
+            for &val in v.iter().skip(1) {
+                ret += format!(", {}", fmt(val)).as_str();
             }
             ret += "]";
             ret
         };
         match self {
-            AliquotSeq::PerfectNumber(n) => {
-                format!("{n}")
-            }
+            AliquotSeq::PerfectNumber(n) => fmt(*n),
             AliquotSeq::PrimeNumber((n, one)) => {
-                format!("{n}, {one}")
+                format!("{}, {}", fmt(*n), fmt(*one))
             }
             AliquotSeq::Convergent(v) => vec_to_string(v),
             AliquotSeq::AmicableNumber((n, m)) => {
-                format!("{n}, {m}")
+                format!("{}, {}", fmt(*n), fmt(*m))
             }
             AliquotSeq::SociableNumber(v) => vec_to_string(v),
             AliquotSeq::AspiringNumber(v) => vec_to_string(v),
@@ -111,13 +208,479 @@ impl<T: Number> AliquotSeq<T> {
         }
     }
 
+    /// Returns the index of the first term exceeding the starting number or
+    /// None, if the sequence never grows beyond its starting number.
+    pub fn first_increase_index(&self) -> Option<usize> {
+        let n = self.number();
+        self.seq().iter().position(|&t| t > n)
+    }
+
+    /// Returns the canonical form of a cycle, which starts at its smallest number.
+    /// Amicable pairs are ordered ascending. A sequence running into a cycle is
+    /// unchanged, since its cycle starts at the number the sequence runs into.
+    pub fn canonical(&self) -> AliquotSeq<T> {
+        match self {
+            AliquotSeq::AmicableNumber((n, m)) if m < n => AliquotSeq::AmicableNumber((*m, *n)),
+            AliquotSeq::SociableNumber(v) => {
+                let pos = v
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, a)| **a)
+                    .map(|(p, _)| p)
+                    .unwrap_or(0);
+                let mut cycle = v.clone();
+                cycle.rotate_left(pos);
+                AliquotSeq::SociableNumber(cycle)
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Returns the terms up to and including the first term exceeding bound.
+    /// All terms are returned, if no term exceeds bound.
+    pub fn truncate_above(&self, bound: T) -> Vec<T> {
+        let mut terms = self.seq();
+        if let Some(pos) = terms.iter().position(|&t| t > bound) {
+            terms.truncate(pos + 1);
+        }
+        terms
+    }
+
+    /// Returns the sum of all terms or None, if the sum exceeds the maximum of T.
+    pub fn term_sum(&self) -> Option<T> {
+        self.seq().into_iter().try_fold(T::ZERO, |sum, t| {
+            if t > (T::MAX - sum) {
+                None
+            } else {
+                Some(sum + t)
+            }
+        })
+    }
+
+    /// Returns the index and value of every term exceeding all previous terms,
+    /// starting with the first term.
+    pub fn record_highs(&self) -> Vec<(usize, T)> {
+        let mut records: Vec<(usize, T)> = vec![];
+        for (i, t) in self.seq().into_iter().enumerate() {
+            if records.last().is_none_or(|&(_, max)| t > max) {
+                records.push((i, t));
+            }
+        }
+        records
+    }
+
+    /// Returns the signed differences of consecutive terms. Differences beyond
+    /// the range of i128 are saturated.
+    pub fn differences(&self) -> Vec<i128> {
+        let diff = |a: T, b: T| -> i128 {
+            if b >= a {
+                i128::try_from((b - a).to_u128()).unwrap_or(i128::MAX)
+            } else {
+                i128::try_from((a - b).to_u128()).map_or(i128::MIN, |d| -d)
+            }
+        };
+        self.seq().windows(2).map(|w| diff(w[0], w[1])).collect()
+    }
+
+    /// Returns the geometric mean of the ratios of consecutive terms. A ratio
+    /// greater than one indicates a growing sequence. Sequences with a single
+    /// term have a ratio of one.
+    pub fn growth_ratio(&self) -> f64 {
+        let terms = self.seq();
+        if terms.len() < 2 {
+            return 1.0;
+        }
+        let log_sum = terms
+            .windows(2)
+            .map(|w| (w[1].to_f64() / w[0].to_f64()).ln())
+            .sum::<f64>();
+        (log_sum / (terms.len() - 1) as f64).exp()
+    }
+
+    /// Returns the fate of the sequence. Undefined sequences are open as well.
+    pub fn fate(&self) -> Fate {
+        match self {
+            AliquotSeq::PrimeNumber(_) | AliquotSeq::Convergent(_) => Fate::Terminates,
+            AliquotSeq::PerfectNumber(_) | AliquotSeq::AspiringNumber(_) => Fate::Perfect,
+            AliquotSeq::AmicableNumber(_)
+            | AliquotSeq::SociableNumber(_)
+            | AliquotSeq::IntoCycle(_, _) => Fate::Cycle,
+            AliquotSeq::Unknown(_, _) => Fate::Open,
+        }
+    }
+
+    /// Returns the reason, why the computation of an unknown sequence stopped.
+    /// Returns None for all other sequences.
+    pub fn unknown_reason(&self) -> Option<UnknownReason> {
+        match self {
+            AliquotSeq::Unknown(_, message) => Some(message.reason),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of members of the cycle the sequence is or runs into,
+    /// e.g. two for amicable numbers. Returns None for sequences without a cycle.
+    pub fn cycle_length(&self) -> Option<usize> {
+        match self {
+            AliquotSeq::AmicableNumber(_) => Some(2),
+            AliquotSeq::SociableNumber(v) => Some(v.len()),
+            AliquotSeq::IntoCycle(_, cycle) => Some(cycle.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns the terms of the sequence together with their indices and whether
+    /// they are members of the cycle the sequence is or runs into. Like for
+    /// cycle_length, perfect numbers don't count as cycles.
+    pub fn annotated_terms(&self) -> Vec<(usize, T, bool)> {
+        let transient = match self {
+            AliquotSeq::AmicableNumber(_) | AliquotSeq::SociableNumber(_) => 0,
+            AliquotSeq::IntoCycle(v, _) => v.len(),
+            _ => self.len(),
+        };
+        self.seq()
+            .into_iter()
+            .enumerate()
+            .map(|(i, t)| (i, t, i >= transient))
+            .collect()
+    }
+
+    /// Returns the number the sequence terminates at. This is either one or a
+    /// perfect number. Returns None for cycles and open sequences.
+    pub fn terminal(&self) -> Option<T> {
+        match self {
+            AliquotSeq::PrimeNumber(_) | AliquotSeq::Convergent(_) => Some(T::ONE),
+            AliquotSeq::PerfectNumber(n) => Some(*n),
+            AliquotSeq::AspiringNumber(v) => v.last().copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns the prime, which a terminating sequence reaches just before one.
+    /// Returns None for all sequences not terminating at one.
+    pub fn terminal_prime(&self) -> Option<T> {
+        match self {
+            AliquotSeq::PrimeNumber((p, _)) => Some(*p),
+            AliquotSeq::Convergent(v) => v.iter().rev().nth(1).copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns the aliquot sequence in a compact binary format. The format
+    /// consists of a tag byte for the variant followed by the terms of the
+    /// sequence, each list prefixed by its length. All numbers are stored in
+    /// little-endian order.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.encode(|bytes, terms| {
+            bytes.extend_from_slice(&(terms.len() as u64).to_le_bytes());
+            for &t in terms {
+                bytes.append(&mut t.to_le_bytes());
+            }
+        })
+    }
+
+    /// Returns the aliquot sequence in the format of to_bytes, but every list
+    /// of terms is delta-encoded: The first term is followed by the differences
+    /// of consecutive terms, which are smaller than the terms for most
+    /// sequences. All numbers are stored as variable-length integers.
+    pub fn compress(&self) -> Vec<u8> {
+        self.encode(|bytes, terms| {
+            write_varint(bytes, terms.len() as u128);
+            let mut prev = 0i128;
+            for &t in terms {
+                // Terms of u128 beyond the maximum of i128 wrap around, but
+                // the wrapped differences are restored by decompress anyway
+                let t = t.to_u128() as i128;
+                let delta = t.wrapping_sub(prev);
+                // Zigzag encoding maps small negative differences to small numbers
+                write_varint(bytes, ((delta << 1) ^ (delta >> 127)) as u128);
+                prev = t;
+            }
+        })
+    }
+
+    /// Returns the tag byte followed by the terms written by write_terms.
+    fn encode(&self, write_terms: impl Fn(&mut Vec<u8>, &[T])) -> Vec<u8> {
+        let mut bytes = vec![];
+        match self {
+            AliquotSeq::PerfectNumber(n) => {
+                bytes.push(0);
+                write_terms(&mut bytes, &[*n]);
+            }
+            AliquotSeq::PrimeNumber((n, one)) => {
+                bytes.push(1);
+                write_terms(&mut bytes, &[*n, *one]);
+            }
+            AliquotSeq::Convergent(v) => {
+                bytes.push(2);
+                write_terms(&mut bytes, v);
+            }
+            AliquotSeq::AmicableNumber((n, m)) => {
+                bytes.push(3);
+                write_terms(&mut bytes, &[*n, *m]);
+            }
+            AliquotSeq::SociableNumber(v) => {
+                bytes.push(4);
+                write_terms(&mut bytes, v);
+            }
+            AliquotSeq::AspiringNumber(v) => {
+                bytes.push(5);
+                write_terms(&mut bytes, v);
+            }
+            AliquotSeq::IntoCycle(v0, v1) => {
+                bytes.push(6);
+                write_terms(&mut bytes, v0);
+                write_terms(&mut bytes, v1);
+            }
+            AliquotSeq::Unknown(v, message) => {
+                bytes.push(7);
+                write_terms(&mut bytes, v);
+                bytes.push(message.reason as u8);
+                bytes.extend_from_slice(&(message.text.len() as u64).to_le_bytes());
+                bytes.extend_from_slice(message.text.as_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Returns the aliquot sequence from the binary format written by to_bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AliquotError> {
+        Self::decode(bytes, |reader| reader.read_terms::<T>())
+    }
+
+    /// Returns the aliquot sequence from the delta-encoded format written by compress.
+    pub fn decompress(bytes: &[u8]) -> Result<Self, AliquotError> {
+        Self::decode(bytes, |reader| reader.read_delta_terms::<T>())
+    }
+
+    /// Returns the approximate number of bytes occupied by the aliquot
+    /// sequence including its terms and reason stored on the heap.
+    pub fn memory_footprint(&self) -> usize {
+        let heap = match self {
+            AliquotSeq::Convergent(v)
+            | AliquotSeq::SociableNumber(v)
+            | AliquotSeq::AspiringNumber(v) => v.capacity() * std::mem::size_of::<T>(),
+            AliquotSeq::IntoCycle(v0, v1) => {
+                (v0.capacity() + v1.capacity()) * std::mem::size_of::<T>()
+            }
+            AliquotSeq::Unknown(v, message) => {
+                v.capacity() * std::mem::size_of::<T>() + message.text.capacity()
+            }
+            _ => 0,
+        };
+        std::mem::size_of::<Self>() + heap
+    }
+
+    /// Returns the aliquot sequence from a tag byte followed by the terms read
+    /// by read_terms.
+    fn decode(
+        bytes: &[u8],
+        read_terms: impl Fn(&mut ByteReader) -> Result<Vec<T>, AliquotError>,
+    ) -> Result<Self, AliquotError> {
+        let mut reader = ByteReader { bytes, pos: 0 };
+        let tag = reader.read(1)?[0];
+        let terms = read_terms(&mut reader)?;
+        if terms.is_empty() {
+            return Err(AliquotError::conversion("Empty sequence"));
+        }
+        // Every variant has a fixed or a minimum number of terms
+        let check_len = |terms: Vec<T>, min: usize, max: usize| -> Result<Vec<T>, AliquotError> {
+            if terms.len() < min || terms.len() > max {
+                let expected = if min == max {
+                    min.to_string()
+                } else {
+                    format!("at least {min}")
+                };
+                let err_msg = format!(
+                    "Expected {expected} numbers for tag {tag}, found {}",
+                    terms.len()
+                );
+                return Err(AliquotError::conversion(err_msg));
+            }
+            Ok(terms)
+        };
+        let aliquot_seq = match tag {
+            0 => AliquotSeq::PerfectNumber(check_len(terms, 1, 1)?[0]),
+            1 => {
+                let terms = check_len(terms, 2, 2)?;
+                AliquotSeq::PrimeNumber((terms[0], terms[1]))
+            }
+            2 => AliquotSeq::Convergent(check_len(terms, 2, usize::MAX)?),
+            3 => {
+                let terms = check_len(terms, 2, 2)?;
+                AliquotSeq::AmicableNumber((terms[0], terms[1]))
+            }
+            4 => AliquotSeq::SociableNumber(check_len(terms, 3, usize::MAX)?),
+            5 => AliquotSeq::AspiringNumber(check_len(terms, 2, usize::MAX)?),
+            6 => {
+                let cycle = check_len(read_terms(&mut reader)?, 2, usize::MAX)?;
+                AliquotSeq::IntoCycle(terms, cycle)
+            }
+            7 => {
+                let unknown_reason = match reader.read(1)?[0] {
+                    0 => UnknownReason::MaxLength,
+                    1 => UnknownReason::MaxValue,
+                    2 => UnknownReason::Arithmetic,
+                    3 => UnknownReason::Interrupted,
+                    4 => UnknownReason::Undefined,
+                    byte => {
+                        let err_msg = format!("Unknown reason {byte}");
+                        return Err(AliquotError::conversion(err_msg));
+                    }
+                };
+                let len = reader.read_len()?;
+                let reason = String::from_utf8(reader.read(len)?.to_vec()).map_err(|err| {
+                    AliquotError::ConversionError {
+                        msg: err.to_string(),
+                        source: Some(Arc::new(err)),
+                    }
+                })?;
+                AliquotSeq::Unknown(terms, UnknownMessage::new(unknown_reason, reason))
+            }
+            _ => {
+                let err_msg = format!("Unknown sequence tag {tag}");
+                return Err(AliquotError::conversion(err_msg));
+            }
+        };
+        if reader.pos != bytes.len() {
+            let err_msg = format!("Unexpected data after byte {}", reader.pos);
+            return Err(AliquotError::conversion(err_msg));
+        }
+        Ok(aliquot_seq)
+    }
+
     /// Returns true, if the aliquot sequence cycles.
     pub fn cycles(&self) -> bool {
-        match self {
-            AliquotSeq::AmicableNumber(_) => true,
-            AliquotSeq::SociableNumber(_) => true,
-            AliquotSeq::IntoCycle(_, _) => true,
-            _ => false,
+        matches!(
+            self,
+            AliquotSeq::AmicableNumber(_)
+                | AliquotSeq::SociableNumber(_)
+                | AliquotSeq::IntoCycle(_, _)
+        )
+    }
+}
+
+/// Appends the number as variable-length integer, which stores seven bits per
+/// byte and sets the highest bit of all bytes except the last one.
+fn write_varint(bytes: &mut Vec<u8>, mut v: u128) {
+    while v >= 0x80 {
+        bytes.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    bytes.push(v as u8);
+}
+
+/// Reads the binary format of aliquot sequences from a slice of bytes.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    /// Returns the next len bytes.
+    fn read(&mut self, len: usize) -> Result<&'a [u8], AliquotError> {
+        if len > (self.bytes.len() - self.pos) {
+            let err_msg = format!("Unexpected end of data at byte {}", self.pos);
+            return Err(AliquotError::conversion(err_msg));
+        }
+        self.pos += len;
+        Ok(&self.bytes[(self.pos - len)..self.pos])
+    }
+
+    /// Returns the next length stored as u64.
+    fn read_len(&mut self) -> Result<usize, AliquotError> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.read(8)?);
+        Ok(u64::from_le_bytes(buf) as usize)
+    }
+
+    /// Returns the next list of numbers prefixed by its length.
+    fn read_terms<T: Number>(&mut self) -> Result<Vec<T>, AliquotError> {
+        let len = self.read_len()?;
+        let width = std::mem::size_of::<T>();
+        let mut terms = vec![];
+        for _ in 0..len {
+            match T::from_le_bytes(self.read(width)?) {
+                Some(t) => terms.push(t),
+                None => {
+                    let err_msg = format!("Invalid number at byte {}", self.pos - width);
+                    return Err(AliquotError::conversion(err_msg));
+                }
+            }
+        }
+        Ok(terms)
+    }
+
+    /// Returns the next variable-length integer written by write_varint.
+    fn read_varint(&mut self) -> Result<u128, AliquotError> {
+        let mut v = 0u128;
+        for shift in (0..128).step_by(7) {
+            let byte = self.read(1)?[0];
+            v |= ((byte & 0x7f) as u128) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        let err_msg = format!("Invalid variable-length integer at byte {}", self.pos);
+        Err(AliquotError::conversion(err_msg))
+    }
+
+    /// Returns the next delta-encoded list of numbers prefixed by its length.
+    fn read_delta_terms<T: Number>(&mut self) -> Result<Vec<T>, AliquotError> {
+        let len = self.read_varint()?;
+        let mut terms = vec![];
+        let mut prev = 0i128;
+        for _ in 0..len {
+            let zigzag = self.read_varint()?;
+            let delta = ((zigzag >> 1) as i128) ^ -((zigzag & 1) as i128);
+            prev = prev.wrapping_add(delta);
+            match T::from_u128(prev as u128) {
+                Some(t) => terms.push(t),
+                None => {
+                    let err_msg = format!("{} exceeds the maximum {}", prev as u128, T::MAX);
+                    return Err(AliquotError::conversion(err_msg));
+                }
+            }
+        }
+        Ok(terms)
+    }
+}
+
+/// Tells how the cache found a sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheOrigin<T: Number> {
+    /// The sequence of the number itself is stored.
+    Direct,
+    /// The sequence has been reconstructed from the stored sequence of parent,
+    /// which contains the number at offset.
+    Reconstructed { parent: T, offset: usize },
+}
+
+/// Decides which sequences are dropped, if a new sequence doesn't fit into
+/// the cache anymore.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CachePolicy {
+    /// The new sequence is not added.
+    #[default]
+    RejectNew,
+    /// The least recently used sequences are evicted.
+    Lru,
+    /// The shortest sequences are evicted, as long as they are shorter than
+    /// the new sequence, since long sequences save the most computation.
+    KeepLongest,
+}
+
+impl FromStr for CachePolicy {
+    type Err = AliquotError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject-new" => Ok(CachePolicy::RejectNew),
+            "lru" => Ok(CachePolicy::Lru),
+            "keep-longest" => Ok(CachePolicy::KeepLongest),
+            _ => Err(AliquotError::InvalidArg(format!(
+                "Unknown cache policy {s}"
+            ))),
         }
     }
 }
@@ -128,6 +691,16 @@ pub struct Cache<T: Number> {
     cache_count: usize,
     cache: HashMap<T, AliquotSeq<T>>,
     cache_lut: HashMap<T, T>,
+    // Number of stored sequences containing each cycle member
+    cycle_members: HashMap<T, usize>,
+    policy: CachePolicy,
+    // Time of the last use of every sequence and the sequences ordered by the
+    // time they were indexed at, only tracked for the LRU policy
+    clock: AtomicU64,
+    last_used: HashMap<T, AtomicU64>,
+    lru_order: BTreeSet<(u64, T)>,
+    // Sequences ordered by their lengths, only tracked for the keep-longest policy
+    by_len: BTreeSet<(usize, T)>,
 }
 
 impl<T: Number> Cache<T> {
@@ -138,9 +711,208 @@ impl<T: Number> Cache<T> {
             cache_count: 0,
             cache: HashMap::<T, AliquotSeq<T>>::new(),
             cache_lut: HashMap::<T, T>::new(),
+            cycle_members: HashMap::<T, usize>::new(),
+            policy: CachePolicy::RejectNew,
+            clock: AtomicU64::new(0),
+            last_used: HashMap::<T, AtomicU64>::new(),
+            lru_order: BTreeSet::<(u64, T)>::new(),
+            by_len: BTreeSet::<(usize, T)>::new(),
+        }
+    }
+
+    /// Returns a new cache with the given policy.
+    pub fn with_policy(max_cache_size: usize, policy: CachePolicy) -> Self {
+        let mut cache = Self::new(max_cache_size);
+        cache.policy = policy;
+        cache
+    }
+
+    /// Returns the policy deciding which sequences are dropped, if the cache is full.
+    pub fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    /// Sets the policy deciding which sequences are dropped, if the cache is full.
+    pub fn set_policy(&mut self, policy: CachePolicy) {
+        self.policy = policy;
+        self.clear_index();
+        let entries = self
+            .cache
+            .iter()
+            .map(|(&n, aliquot_seq)| (n, aliquot_seq.len()))
+            .collect::<Vec<(T, usize)>>();
+        for (n, len) in entries {
+            self.index(n, len);
+        }
+    }
+
+    /// Marks the sequence stored for n as used just now. The amicable number
+    /// stored in reverse order is marked together with n. Only the time of
+    /// the last use is updated, the LRU order is fixed lazily by victim.
+    fn touch(&self, n: T) {
+        if self.policy != CachePolicy::Lru {
+            return;
+        }
+        let now = self.clock.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+        if let Some(last_used) = self.last_used.get(&n) {
+            last_used.store(now, AtomicOrdering::Relaxed);
+        }
+        if let Some(AliquotSeq::AmicableNumber((_, p))) = self.cache.get(&n)
+            && let Some(last_used) = self.last_used.get(p)
+        {
+            last_used.store(now, AtomicOrdering::Relaxed);
+        }
+    }
+
+    /// Adds the sequence stored for n to the index of the policy.
+    fn index(&mut self, n: T, len: usize) {
+        match self.policy {
+            CachePolicy::RejectNew => {}
+            CachePolicy::Lru => {
+                let now = self.clock.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+                self.last_used.insert(n, AtomicU64::new(now));
+                self.lru_order.insert((now, n));
+            }
+            CachePolicy::KeepLongest => {
+                self.by_len.insert((len, n));
+            }
+        }
+    }
+
+    /// Removes the sequence stored for n from the index of the policy.
+    fn unindex(&mut self, n: T, len: usize) {
+        if let Some(last_used) = self.last_used.remove(&n) {
+            self.lru_order.remove(&(last_used.into_inner(), n));
+        }
+        self.by_len.remove(&(len, n));
+    }
+
+    /// Removes all sequences from the indices of the policies.
+    fn clear_index(&mut self) {
+        self.last_used.clear();
+        self.lru_order.clear();
+        self.by_len.clear();
+    }
+
+    /// Returns the terms of the sequence, which are added to the LUT.
+    fn lut_terms(aliquot_seq: &AliquotSeq<T>) -> Option<&[T]> {
+        match aliquot_seq {
+            AliquotSeq::Convergent(seq)
+            | AliquotSeq::SociableNumber(seq)
+            | AliquotSeq::AspiringNumber(seq)
+            | AliquotSeq::IntoCycle(seq, _)
+            | AliquotSeq::Unknown(seq, _) => Some(seq),
+            _ => None,
+        }
+    }
+
+    /// Returns the members of the cycle contained in the sequence.
+    fn cycle_terms(aliquot_seq: &AliquotSeq<T>) -> Vec<T> {
+        match aliquot_seq {
+            AliquotSeq::AmicableNumber((a0, a1)) => vec![*a0, *a1],
+            AliquotSeq::SociableNumber(seq) => seq.clone(),
+            AliquotSeq::IntoCycle(_, cycle) => cycle.clone(),
+            _ => vec![],
+        }
+    }
+
+    /// Removes the members of the cycle of a sequence, which is not stored anymore.
+    fn unregister_cycle(&mut self, aliquot_seq: &AliquotSeq<T>) {
+        for m in Self::cycle_terms(aliquot_seq) {
+            if let Some(count) = self.cycle_members.get_mut(&m) {
+                *count -= 1;
+                if *count == 0 {
+                    self.cycle_members.remove(&m);
+                }
+            }
+        }
+    }
+
+    /// Stores the sequence for n and registers the members of its cycle.
+    fn insert_seq(&mut self, n: T, aliquot_seq: AliquotSeq<T>) {
+        for m in Self::cycle_terms(&aliquot_seq) {
+            *self.cycle_members.entry(m).or_insert(0) += 1;
+        }
+        let len = aliquot_seq.len();
+        if let Some(replaced) = self.cache.insert(n, aliquot_seq) {
+            self.unregister_cycle(&replaced);
+            self.unindex(n, replaced.len());
+        }
+        self.index(n, len);
+    }
+
+    /// Removes the sequence stored for n and unregisters the members of its cycle.
+    fn remove_seq(&mut self, n: T) -> Option<AliquotSeq<T>> {
+        let aliquot_seq = self.cache.remove(&n)?;
+        self.unregister_cycle(&aliquot_seq);
+        self.unindex(n, aliquot_seq.len());
+        Some(aliquot_seq)
+    }
+
+    /// Removes the sequence stored for n together with its LUT entries.
+    fn remove(&mut self, n: T) {
+        let Some(aliquot_seq) = self.remove_seq(n) else {
+            return;
+        };
+        self.cache_count -= aliquot_seq.len();
+        if let Some(seq) = Self::lut_terms(&aliquot_seq) {
+            for s in seq.iter().skip(1) {
+                if self.cache_lut.get(s) == Some(&n) {
+                    self.cache_lut.remove(s);
+                }
+            }
+            self.cache_count -= seq.len() - 1;
+        }
+        // The amicable number was added in both orders
+        if let AliquotSeq::AmicableNumber((_, p)) = aliquot_seq
+            && self.cache.get(&p) == Some(&AliquotSeq::AmicableNumber((p, n)))
+        {
+            self.remove_seq(p);
+        }
+    }
+
+    /// Returns the number of the sequence to evict for a new sequence of the
+    /// given length or None, if no sequence should be evicted.
+    fn victim(&mut self, len: usize) -> Option<T> {
+        match self.policy {
+            CachePolicy::RejectNew => None,
+            CachePolicy::Lru => {
+                // Sequences used since they were ordered are moved to their
+                // time of the last use, removed sequences are skipped
+                while let Some((time, n)) = self.lru_order.pop_first() {
+                    let Some(last_used) = self.last_used.get_mut(&n) else {
+                        continue;
+                    };
+                    let last_used = *last_used.get_mut();
+                    if last_used == time {
+                        return Some(n);
+                    }
+                    self.lru_order.insert((last_used, n));
+                }
+                None
+            }
+            CachePolicy::KeepLongest => self
+                .by_len
+                .first()
+                .filter(|(shortest, _)| *shortest < len)
+                .map(|(_, n)| *n),
         }
     }
 
+    /// Returns a new cache, which holds as many numbers as fit into the given
+    /// number of bytes.
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self::new(Self::capacity_for_bytes(max_bytes))
+    }
+
+    /// Returns the number of numbers fitting into the given number of bytes.
+    /// Every number is stored in a sequence and in the LUT, which is a hash
+    /// map storing a pair of numbers, so we estimate four times the size of T.
+    pub fn capacity_for_bytes(max_bytes: usize) -> usize {
+        const OVERHEAD_FACTOR: usize = 4;
+        max_bytes / (std::mem::size_of::<T>() * OVERHEAD_FACTOR)
+    }
+
     /// Adds the whole sequence to the LUT, except the first number.
     fn add_seq_lut(&mut self, n: T, seq: &[T]) {
         for &s in seq.iter().skip(1) {
@@ -151,12 +923,23 @@ impl<T: Number> Cache<T> {
         self.cache_count += seq.len() - 1;
     }
 
-    /// Adds the aliquot sequence to the cache, if it isn't present yet.
+    /// Adds the aliquot sequence to the cache, if it isn't present yet. If the
+    /// cache is full, the policy decides whether other sequences are evicted
+    /// or the new sequence is rejected.
     pub fn add(&mut self, aliquot_seq: AliquotSeq<T>) {
         let len = aliquot_seq.len();
         let n = aliquot_seq.number();
+        // Make room for the sequence, if it fits into the cache at all
+        if len < self.max_cache_size && !self.cache.contains_key(&n) {
+            while len >= self.max_cache_size.saturating_sub(self.cache_count) {
+                match self.victim(len) {
+                    Some(victim) => self.remove(victim),
+                    None => break,
+                }
+            }
+        }
         // Check if sequence fits into cache
-        if len < (self.max_cache_size - self.cache_count) {
+        if len < self.max_cache_size.saturating_sub(self.cache_count) {
             // Check if number n exists in cache already
             if !self.cache.contains_key(&n) {
                 match aliquot_seq {
@@ -172,7 +955,7 @@ impl<T: Number> Cache<T> {
                     AliquotSeq::AmicableNumber((_, p)) => {
                         // Add the amicable number in reverse order
                         // We don't need the LUT in this case
-                        self.cache.insert(p, AliquotSeq::AmicableNumber((p, n)));
+                        self.insert_seq(p, AliquotSeq::AmicableNumber((p, n)));
                     }
                     AliquotSeq::IntoCycle(ref seq, _) => {
                         self.add_seq_lut(n, seq);
@@ -182,7 +965,7 @@ impl<T: Number> Cache<T> {
                     }
                     _ => {}
                 }
-                self.cache.insert(n, aliquot_seq);
+                self.insert_seq(n, aliquot_seq);
                 self.cache_count += len;
             }
         }
@@ -196,10 +979,55 @@ impl<T: Number> Cache<T> {
         aliquot_seq
     }
 
+    /// Adds all prime numbers and perfect numbers up to the given number to the
+    /// cache, so they are recognized without computing their aliquot sums.
+    pub fn seed(&mut self, up_to: T) {
+        let limit = match up_to.try_into() {
+            Ok(limit) => limit as usize + 1,
+            Err(_) => return,
+        };
+        let sieve = Sieve::new(limit);
+        for k in 2..sieve.limit() {
+            let Ok(n) = T::try_from(k) else {
+                break;
+            };
+            if sieve.is_prime(k) {
+                self.add(AliquotSeq::PrimeNumber((n, T::ONE)));
+            } else if sieve.aliquot_sum(k) == Some(k) {
+                self.add(AliquotSeq::PerfectNumber(n));
+            }
+        }
+    }
+
     /// Clears all entries in the cache without deallocating memory.
     pub fn clear(&mut self) {
         self.cache_count = 0;
         self.cache.clear();
+        self.cache_lut.clear();
+        self.cycle_members.clear();
+        self.clear_index();
+    }
+
+    /// Rebuilds the LUT and the count of numbers solely from the stored
+    /// sequences. This drops LUT entries pointing to evicted sequences.
+    pub fn compact(&mut self) {
+        self.cache_lut.clear();
+        self.cache_count = 0;
+        let cache = std::mem::take(&mut self.cache);
+        for (&n, aliquot_seq) in cache.iter() {
+            // The amicable number added in both orders is only counted once
+            if let AliquotSeq::AmicableNumber((_, p)) = aliquot_seq
+                && *p < n
+                && cache.get(p) == Some(&AliquotSeq::AmicableNumber((*p, n)))
+            {
+                continue;
+            }
+            self.cache_count += aliquot_seq.len();
+            if let Some(seq) = Self::lut_terms(aliquot_seq) {
+                self.add_seq_lut(n, seq);
+            }
+        }
+        self.cache = cache;
     }
 
     /// Returns the number of sequences stored in the cache.
@@ -212,8 +1040,63 @@ impl<T: Number> Cache<T> {
         self.cache_count
     }
 
+    /// Returns true, if n is a member of any cycle stored in the cache.
+    pub fn is_in_cycle(&self, n: T) -> bool {
+        self.cycle_members.contains_key(&n)
+    }
+
+    /// Returns an iterator over all stored sequences in ascending order of their numbers.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &AliquotSeq<T>)> {
+        let mut entries = self.cache.iter().collect::<Vec<(&T, &AliquotSeq<T>)>>();
+        entries.sort_by_key(|(a, _)| **a);
+        entries.into_iter()
+    }
+
+    /// Returns all stored cycles of amicable and sociable numbers in canonical
+    /// form. Every cycle is returned once in ascending order of its smallest number.
+    pub fn cycles(&self) -> Vec<AliquotSeq<T>> {
+        let cycles = self
+            .cache
+            .values()
+            .filter(|aliquot_seq| {
+                matches!(
+                    aliquot_seq,
+                    AliquotSeq::AmicableNumber(_) | AliquotSeq::SociableNumber(_)
+                )
+            })
+            .map(|aliquot_seq| aliquot_seq.canonical())
+            .collect::<Vec<AliquotSeq<T>>>();
+        Self::merge_cycles(cycles)
+    }
+
+    /// Merges cycles in canonical form, for example returned by the caches of
+    /// several threads, so every cycle is contained once in ascending order of
+    /// its smallest number.
+    pub fn merge_cycles(mut cycles: Vec<AliquotSeq<T>>) -> Vec<AliquotSeq<T>> {
+        cycles.sort_by_key(|aliquot_seq| aliquot_seq.number());
+        cycles.dedup();
+        cycles
+    }
+
+    /// Returns the stored sequences mapped by their numbers.
+    pub fn sequences(&self) -> &HashMap<T, AliquotSeq<T>> {
+        &self.cache
+    }
+
+    /// Returns the LUT mapping numbers inside stored sequences to the numbers
+    /// of these sequences.
+    pub fn lut(&self) -> &HashMap<T, T> {
+        &self.cache_lut
+    }
+
     /// Returns the aliquot sequence for n or None, if there is no entry in the cache.
     pub fn get(&self, n: T) -> Option<AliquotSeq<T>> {
+        self.get_with_origin(n).map(|(aliquot_seq, _)| aliquot_seq)
+    }
+
+    /// Returns the aliquot sequence for n together with the way it has been
+    /// found in the cache or None, if there is no entry in the cache.
+    pub fn get_with_origin(&self, n: T) -> Option<(AliquotSeq<T>, CacheOrigin<T>)> {
         let find_pos_n = move |seq: &Vec<T>| -> Option<usize> {
             seq.iter()
                 .enumerate()
@@ -221,45 +1104,54 @@ impl<T: Number> Cache<T> {
                 .map(|(p, _)| p)
         };
         if let Some(aliquot_seq) = self.cache.get(&n) {
-            return Some(aliquot_seq.clone());
-        } else if let Some(p) = self.cache_lut.get(&n) {
+            self.touch(n);
+            return Some((aliquot_seq.clone(), CacheOrigin::Direct));
+        } else if let Some(&p) = self.cache_lut.get(&n) {
+            self.touch(p);
+            let origin = |offset| CacheOrigin::Reconstructed { parent: p, offset };
             // Reconstruct the sequence
-            match self.cache.get(p) {
+            match self.cache.get(&p) {
                 Some(AliquotSeq::Convergent(seq)) => {
-                    if let Some(pos) = find_pos_n(seq) {
-                        if pos < (seq.len() - 1) {
-                            let seq_new = seq[pos..].to_vec();
-                            return Some(AliquotSeq::Convergent(seq_new));
+                    if let Some(pos) = find_pos_n(seq)
+                        && pos < (seq.len() - 1)
+                    {
+                        if pos == (seq.len() - 2) && seq[pos + 1] == T::ONE {
+                            // Only the prime followed by one is left, but a custom
+                            // terminal value may follow any other number
+                            return Some((AliquotSeq::PrimeNumber((n, seq[pos + 1])), origin(pos)));
                         }
+                        let seq_new = seq[pos..].to_vec();
+                        return Some((AliquotSeq::Convergent(seq_new), origin(pos)));
                     }
                 }
                 Some(AliquotSeq::AspiringNumber(seq)) => {
-                    if let Some(pos) = find_pos_n(seq) {
-                        if pos < (seq.len() - 1) {
-                            let seq_new = seq[pos..].to_vec();
-                            return Some(AliquotSeq::AspiringNumber(seq_new));
-                        }
+                    if let Some(pos) = find_pos_n(seq)
+                        && pos < (seq.len() - 1)
+                    {
+                        let seq_new = seq[pos..].to_vec();
+                        return Some((AliquotSeq::AspiringNumber(seq_new), origin(pos)));
                     }
                 }
                 Some(AliquotSeq::SociableNumber(seq)) => {
                     if let Some(pos) = find_pos_n(seq) {
                         let mut seq_new = seq[pos..].to_vec();
                         seq_new.extend_from_slice(&seq[0..pos]);
-                        return Some(AliquotSeq::SociableNumber(seq_new));
+                        return Some((AliquotSeq::SociableNumber(seq_new), origin(pos)));
                     }
                 }
                 Some(AliquotSeq::IntoCycle(seq, cycle)) => {
                     if let Some(pos) = find_pos_n(seq) {
                         let seq_new = seq[pos..].to_vec();
-                        return Some(AliquotSeq::IntoCycle(seq_new, cycle.clone()));
+                        return Some((AliquotSeq::IntoCycle(seq_new, cycle.clone()), origin(pos)));
                     }
                 }
-                Some(AliquotSeq::Unknown(seq, reason)) => {
-                    if let Some(pos) = find_pos_n(seq) {
-                        if pos < (seq.len() - 1) {
-                            let seq_new = seq[pos..].to_vec();
-                            return Some(AliquotSeq::Unknown(seq_new, reason.clone()));
-                        }
+                Some(AliquotSeq::Unknown(seq, message)) => {
+                    if let Some(pos) = find_pos_n(seq)
+                        && pos < (seq.len() - 1)
+                    {
+                        let seq_new = seq[pos..].to_vec();
+                        let aliquot_seq = AliquotSeq::Unknown(seq_new, message.clone());
+                        return Some((aliquot_seq, origin(pos)));
                     }
                 }
                 _ => {}
@@ -269,33 +1161,63 @@ impl<T: Number> Cache<T> {
     }
 }
 
-/// Generator for aliquot sequences.
-pub struct Generator<T: Number> {
-    max_num: T,
-    max_len_seq: usize,
+/// Function computing the next number of a sequence, which is the aliquot sum
+/// for aliquot sequences. Other functions like the unitary aliquot sum can be
+/// used to compute generalized sequences.
+pub trait StepFn<T: Number> {
+    fn step(&self, n: T) -> Result<T, AliquotError>;
+}
+
+/// Step function of ordinary aliquot sequences.
+pub struct AliquotSum;
+
+impl<T: Number> StepFn<T> for AliquotSum {
+    fn step(&self, n: T) -> Result<T, AliquotError> {
+        Generator::<T>::aliquot_sum(n)
+    }
+}
+
+impl<T: Number, F: Fn(T) -> Result<T, AliquotError>> StepFn<T> for F {
+    fn step(&self, n: T) -> Result<T, AliquotError> {
+        self(n)
+    }
+}
+
+/// Generator for aliquot sequences.
+pub struct Generator<T: Number> {
+    max_num: T,
+    max_len_seq: usize,
     cache: Cache<T>,
+    sieve: Option<Sieve>,
+    stats: Option<StatsHandle>,
+    track_factored: bool,
+    factored: HashSet<T>,
+    cycle_window: Option<usize>,
+    terminal: T,
+    // The logger decides about debug output with the log feature enabled
+    #[cfg_attr(feature = "log", allow(dead_code))]
     debug: bool,
 }
 
-impl<T: Number> Default for Generator<T>
-where
-    Range<T>: Iterator<Item = T>,
-{
+impl<T: Number> Default for Generator<T> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<T: Number> Generator<T>
-where
-    Range<T>: Iterator<Item = T>,
-{
+impl<T: Number> Generator<T> {
     /// Returns a new generator object for aliquot sequences with defaullt values.
     pub fn new() -> Self {
         Self {
             max_num: T::MAX,
             max_len_seq: 1_000_000,
             cache: Cache::new(1_000_000),
+            sieve: None,
+            stats: None,
+            track_factored: false,
+            factored: HashSet::new(),
+            cycle_window: None,
+            terminal: T::ONE,
             debug: false,
         }
     }
@@ -307,17 +1229,125 @@ where
             max_num,
             max_len_seq,
             cache,
+            sieve: None,
+            stats: None,
+            track_factored: false,
+            factored: HashSet::new(),
+            cycle_window: None,
+            terminal: T::ONE,
             debug,
         }
     }
 
-    /// Prints string, if debug is enabled.
+    /// Returns a new generator with the same parameters, sieve and stats handle,
+    /// but an empty cache of the same size. The cache is NOT cloned, so both
+    /// generators can be used independently, e.g. in different threads.
+    pub fn fork(&self) -> Self {
+        Self {
+            max_num: self.max_num,
+            max_len_seq: self.max_len_seq,
+            cache: Cache::with_policy(self.cache.max_cache_size, self.cache.policy),
+            sieve: self.sieve.clone(),
+            stats: self.stats.clone(),
+            track_factored: self.track_factored,
+            factored: HashSet::new(),
+            cycle_window: self.cycle_window,
+            terminal: self.terminal,
+            debug: self.debug,
+        }
+    }
+
+    /// Sets the sieve used to compute aliquot sums of numbers within its limit.
+    /// Numbers beyond the limit are still computed using trial division.
+    pub fn set_sieve(&mut self, sieve: Option<Sieve>) {
+        self.sieve = sieve;
+    }
+
+    /// Sets the handle to the stats, which count the types of all sequences
+    /// returned by aliquot_seq and aliquot_seq_interruptible.
+    pub fn set_stats_handle(&mut self, stats: Option<StatsHandle>) {
+        self.stats = stats;
+    }
+
+    /// Sets the number of the last terms of a sequence, which are kept to
+    /// detect running into a cycle. None keeps all terms, which is the default.
+    /// A window bounds the memory for very long sequences, but cycles longer
+    /// than the window are not detected and these sequences are classified as
+    /// unknown once they reach the maximum length. Cycles containing the first
+    /// number of the sequence are always detected.
+    pub fn set_cycle_window(&mut self, window: Option<usize>) {
+        self.cycle_window = window;
+    }
+
+    /// Enables or disables recording all numbers, whose aliquot sums are computed
+    /// while computing sequences. Disabling clears the recorded numbers.
+    pub fn track_factored(&mut self, track: bool) {
+        self.track_factored = track;
+        if !track {
+            self.factored = HashSet::new();
+        }
+    }
+
+    /// Returns all distinct numbers, whose aliquot sums have been computed while
+    /// computing sequences. Numbers are only recorded with track_factored enabled.
+    pub fn factored_numbers(&self) -> &HashSet<T> {
+        &self.factored
+    }
+
+    /// Adds the given sequences to the cache, e.g. known results loaded from disk.
+    pub fn preload(&mut self, seqs: impl IntoIterator<Item = AliquotSeq<T>>) {
+        for aliquot_seq in seqs {
+            self.cache.add(aliquot_seq);
+        }
+    }
+
+    /// Computes the sequences of all numbers from 2 below up_to in ascending
+    /// order. Unlike seeding the cache with primes and perfect numbers, the full
+    /// sequences of the small numbers are cached, which most sequences of larger
+    /// numbers descend into.
+    pub fn warmup(&mut self, up_to: T) {
+        for n in iter_range(T::TWO..up_to) {
+            self.aliquot_seq(n);
+        }
+    }
+
+    /// Sets the value, which terminates a sequence like one does. Sequences
+    /// reaching this value are convergent. The cache is cleared, since its
+    /// sequences may have been computed with another terminal value.
+    pub fn set_terminal_value(&mut self, terminal: T) {
+        self.terminal = terminal;
+        self.cache.clear();
+    }
+
+    /// Prints string, if debug is enabled. With the log feature enabled the
+    /// string is passed to the logger instead.
     fn print_debug(&self, line: String) {
+        #[cfg(feature = "log")]
+        log::debug!("{line}");
+        #[cfg(not(feature = "log"))]
         if self.debug {
             println!("Debug: {line}");
         }
     }
 
+    /// Prints string of errors stopping a sequence, if debug is enabled. With
+    /// the log feature enabled the string is logged at warn level.
+    fn print_warning(&self, line: String) {
+        #[cfg(feature = "log")]
+        log::warn!("{line}");
+        #[cfg(not(feature = "log"))]
+        self.print_debug(line);
+    }
+
+    /// Prints string of frequent events like cache hits, if debug is enabled.
+    /// With the log feature enabled the string is logged at trace level.
+    fn print_trace(&self, line: String) {
+        #[cfg(feature = "log")]
+        log::trace!("{line}");
+        #[cfg(not(feature = "log"))]
+        self.print_debug(line);
+    }
+
     /// Sums up all proper divisors of a number n (except n itself).
     pub fn aliquot_sum(n: T) -> Result<T, AliquotError> {
         // The aliquot sum is always zero for one and undefined for zero
@@ -340,10 +1370,9 @@ where
             x0
         };
         let end = isqrt(n) + T::ONE;
-        for i in start..end {
-            let div = n / i;
-            let chk = i * div;
-            if chk == n {
+        for i in iter_range(start..end) {
+            if n % i == T::ZERO {
+                let div = n / i;
                 let add = if i != div {
                     // Both i and div are divisors of n
                     i + div
@@ -361,241 +1390,1723 @@ where
         Ok(sum)
     }
 
-    /// Computes the aliquot sequence of a number n.
-    pub fn aliquot_seq(&mut self, n: T) -> AliquotSeq<T> {
-        // Store all values in a hash map for detecting cycles faster
-        let mut lut_seq = HashSet::<T>::new();
-        // The original number is the first number in the sequence
-        let mut seq = vec![n];
-        // Aliquot sequence is undefined for 0
-        if n == T::ZERO || n == T::ONE {
-            return AliquotSeq::Unknown(seq, "Undefined".to_string());
-        }
-        // Check if the aliquot sequence has been computed for this number already
-        if let Some(aliquot_seq_cache) = self.cache.get(n) {
-            self.print_debug(format!("Found sequence for {n} in the cache"));
-            return aliquot_seq_cache;
+    /// Returns all proper divisors of a number n in ascending order.
+    pub fn proper_divisors(n: T) -> Vec<T> {
+        if n <= T::ONE {
+            return vec![];
         }
-        for _i in 1..self.max_len_seq {
-            let len_seq = seq.len();
-            let last = seq[len_seq - 1];
-            match Self::aliquot_sum(last) {
-                Ok(next) => {
-                    // Abort, if a number in the sequence exceeds the maximum value allowed
-                    if next >= self.max_num {
-                        self.print_debug(format!("Numbers in the sequence for {n} exceed maximum"));
-                        let reason = format!("Maximum value {} exceeded", self.max_num);
-                        return self.cache.add_and_return(AliquotSeq::Unknown(seq, reason));
-                    }
-                    // First check if the sum is stored in the cache, so we don't need
-                    // to compute the rest of the sequence
-                    if let Some(aliquot_seq_cache) = self.cache.get(next) {
-                        self.print_debug(format!("Found sequence for {next} in the cache to complete the sequence for {n}"));
-                        match aliquot_seq_cache {
-                            AliquotSeq::PerfectNumber(p) => {
-                                seq.push(p);
-                                return self.cache.add_and_return(AliquotSeq::AspiringNumber(seq));
-                            }
-                            AliquotSeq::PrimeNumber((p, one)) => {
-                                seq.push(p);
-                                seq.push(one);
-                                return self.cache.add_and_return(AliquotSeq::Convergent(seq));
-                            }
-                            AliquotSeq::Convergent(v) => {
-                                seq.extend_from_slice(v.as_slice());
-                                return self.cache.add_and_return(AliquotSeq::Convergent(seq));
-                            }
-                            AliquotSeq::AmicableNumber((a0, a1)) => {
-                                // Check if this is just the reverse order
-                                if a0 == next && a1 == n {
-                                    return AliquotSeq::AmicableNumber((n, next));
-                                } else {
-                                    // Otherwise n runs into cycle of amicable numbers
-                                    return self
-                                        .cache
-                                        .add_and_return(AliquotSeq::IntoCycle(seq, vec![a0, a1]));
-                                }
-                            }
-                            AliquotSeq::SociableNumber(v) => {
-                                // Runs into a cycle of sociable numbers
-                                return self
-                                    .cache
-                                    .add_and_return(AliquotSeq::IntoCycle(seq, v.clone()));
-                            }
-                            AliquotSeq::AspiringNumber(v) => {
-                                seq.extend_from_slice(v.as_slice());
-                                return self.cache.add_and_return(AliquotSeq::AspiringNumber(seq));
-                            }
-                            AliquotSeq::IntoCycle(v0, v1) => {
-                                seq.extend_from_slice(v0.as_slice());
-                                return self
-                                    .cache
-                                    .add_and_return(AliquotSeq::IntoCycle(seq, v1.clone()));
-                            }
-                            AliquotSeq::Unknown(v, reason) => {
-                                // We ran into an unknown sequence
-                                seq.extend_from_slice(v.as_slice());
-                                return self.cache.add_and_return(AliquotSeq::Unknown(seq, reason));
-                            }
-                        }
-                    } else if next == T::ONE {
-                        self.print_debug(format!("Sequence for {n} converged to one"));
-                        match len_seq {
-                            1 => {
-                                // If only n is contained in the sequence so far, we have a prime
-                                return self
-                                    .cache
-                                    .add_and_return(AliquotSeq::PrimeNumber((n, T::ONE)));
-                            }
-                            _ => {
-                                // This is a normal sequence ending with a prime followed by one
-                                seq.push(T::ONE);
-                                return self.cache.add_and_return(AliquotSeq::Convergent(seq));
-                            }
-                        }
-                    } else if next == n {
-                        self.print_debug(format!("Sequence for {n} converged to {n}"));
-                        match len_seq {
-                            1 => {
-                                // There is only the original number in the sequence
-                                // so this must be a perfect number
-                                return self.cache.add_and_return(AliquotSeq::PerfectNumber(n));
-                            }
-                            2 => {
-                                // This is a repeating sequence with two numbers
-                                return self
-                                    .cache
-                                    .add_and_return(AliquotSeq::AmicableNumber((n, last)));
-                            }
-                            _ => {
-                                // This is a repeating sequence with more than two numbers
-                                return self.cache.add_and_return(AliquotSeq::SociableNumber(seq));
-                            }
-                        }
-                    } else if next == last {
-                        self.print_debug(format!(
-                            "Sequence for {n} converged into the perfect number {last}"
-                        ));
-                        // This sequence ended with a perfect number, so we have an aspiring number
-                        return self.cache.add_and_return(AliquotSeq::AspiringNumber(seq));
-                    } else if lut_seq.contains(&next) {
-                        self.print_debug(format!(
-                            "Sequence for {n} converged into a cycle of {next}"
-                        ));
-                        // Find the position in the sequence and split there
-                        // We now have a sequence, which converges into a cycle
-                        // since the original number n is not contained in the LUT
-                        let pos = seq
-                            .iter()
-                            .enumerate()
-                            .find(|&(_, &x)| x == next)
-                            .map(|(p, _)| p)
-                            .unwrap_or(0);
-                        let cycle = seq.split_off(pos);
-                        return self.cache.add_and_return(AliquotSeq::IntoCycle(seq, cycle));
-                    }
-                    seq.push(next);
-                    lut_seq.insert(next);
-                }
-                Err(err_msg) => {
-                    self.print_debug(format!(
-                        "Sequence of {n} unknown, because an error occurred"
-                    ));
-                    println!("Error: {err_msg}");
-                    let reason = format!("{err_msg}");
-                    return self.cache.add_and_return(AliquotSeq::Unknown(seq, reason));
+        let mut small = vec![T::ONE];
+        let mut large = vec![];
+        let mut i = T::TWO;
+        while i <= n / i {
+            if n % i == T::ZERO {
+                let div = n / i;
+                small.push(i);
+                if i != div {
+                    large.push(div);
                 }
             }
+            i += T::ONE;
         }
-        let reason = format!("Maximum length {} of sequence exceeded", self.max_len_seq);
-        self.cache.add_and_return(AliquotSeq::Unknown(seq, reason))
+        small.extend(large.into_iter().rev());
+        small
     }
 
-    /// Returns the associated cache object.
-    pub fn cache(&self) -> &Cache<T> {
-        &self.cache
+    /// Returns all pairs of divisors (d, n / d) with d <= n / d in ascending order of d.
+    pub fn divisor_pairs(n: T) -> Result<Vec<(T, T)>, AliquotError> {
+        if n == T::ZERO {
+            let err_msg = "Divisors of zero are undefined".to_string();
+            return Err(AliquotError::InvalidArg(err_msg));
+        }
+        let mut pairs = vec![];
+        let mut i = T::ONE;
+        while i <= n / i {
+            if n % i == T::ZERO {
+                pairs.push((i, n / i));
+            }
+            i += T::ONE;
+        }
+        Ok(pairs)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Returns the pairs (d, s(d)) of every divisor d of n including n itself,
+    /// where s(d) is the aliquot sum of d. Sums found in the cache are not
+    /// computed again.
+    pub fn divisor_aliquot_sums(&mut self, n: T) -> Result<Vec<(T, T)>, AliquotError> {
+        if n == T::ZERO {
+            let err_msg = "Divisors of zero are undefined".to_string();
+            return Err(AliquotError::InvalidArg(err_msg));
+        }
+        let mut divisors = Self::proper_divisors(n);
+        divisors.push(n);
+        divisors
+            .into_iter()
+            .map(|d| Ok((d, self.cached_sum(d)?)))
+            .collect()
+    }
 
-    fn test_gen<T: Number>(gener: &mut Generator<T>, n: T, exp: AliquotSeq<T>)
-    where
-        Range<T>: Iterator<Item = T>,
-    {
-        assert_eq!(gener.aliquot_seq(n), exp);
+    /// Returns the aliquot sum of n taken from a cached sequence containing n
+    /// or computes it, if n is not cached.
+    fn cached_sum(&self, n: T) -> Result<T, AliquotError> {
+        match self.cache.get(n) {
+            Some(AliquotSeq::PerfectNumber(_)) => Ok(n),
+            Some(aliquot_seq) if aliquot_seq.len() > 1 => Ok(aliquot_seq.seq()[1]),
+            _ => self.next_term(n),
+        }
     }
 
-    #[test]
-    fn test_aliquot_seq_u16() {
-        let mut gener = Generator::<u16>::new();
-        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
-        test_gen(&mut gener, 3, AliquotSeq::PrimeNumber((3, 1)));
-        test_gen(&mut gener, 5, AliquotSeq::PrimeNumber((5, 1)));
-        test_gen(&mut gener, 6, AliquotSeq::PerfectNumber(6));
-        test_gen(&mut gener, 7, AliquotSeq::PrimeNumber((7, 1)));
-        test_gen(&mut gener, 11, AliquotSeq::PrimeNumber((11, 1)));
-        test_gen(
-            &mut gener,
-            12,
-            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]),
-        );
-        test_gen(&mut gener, 13, AliquotSeq::PrimeNumber((13, 1)));
-        test_gen(&mut gener, 17, AliquotSeq::PrimeNumber((17, 1)));
-        test_gen(&mut gener, 19, AliquotSeq::PrimeNumber((19, 1)));
-        test_gen(&mut gener, 28, AliquotSeq::PerfectNumber(28));
-        test_gen(&mut gener, 29, AliquotSeq::PrimeNumber((29, 1)));
-        test_gen(
-            &mut gener,
-            30,
-            AliquotSeq::Convergent(vec![
-                30, 42, 54, 66, 78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1,
-            ]),
-        );
-        test_gen(&mut gener, 31, AliquotSeq::PrimeNumber((31, 1)));
-        test_gen(&mut gener, 41, AliquotSeq::PrimeNumber((41, 1)));
-        test_gen(
-            &mut gener,
-            42,
-            AliquotSeq::Convergent(vec![42, 54, 66, 78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
-        );
-        test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
-        test_gen(
-            &mut gener,
-            54,
-            AliquotSeq::Convergent(vec![54, 66, 78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
-        );
-        test_gen(
-            &mut gener,
-            60,
-            AliquotSeq::Convergent(vec![60, 108, 172, 136, 134, 70, 74, 40, 50, 43, 1]),
-        );
-        test_gen(
-            &mut gener,
-            78,
-            AliquotSeq::Convergent(vec![78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
-        );
-        test_gen(
-            &mut gener,
-            90,
-            AliquotSeq::Convergent(vec![90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
-        );
-        test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
-        test_gen(
-            &mut gener,
-            96,
-            AliquotSeq::Convergent(vec![96, 156, 236, 184, 176, 196, 203, 37, 1]),
-        );
-        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
-        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
+    /// Sums up all divisors of a number n including n itself.
+    pub fn sigma(n: T) -> Result<T, AliquotError> {
+        Self::add_checked(Self::aliquot_sum(n)?, n)
     }
 
-    #[test]
-    fn test_aliquot_seq_u32() {
-        let mut gener = Generator::<u32>::new();
-        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
+    /// Adds two numbers and returns an overflow error, if the sum exceeds the maximum of T.
+    fn add_checked(a: T, b: T) -> Result<T, AliquotError> {
+        if b > (T::MAX - a) {
+            let err_msg = format!("{} plus {} exceeds maximum {}", a, b, T::MAX);
+            return Err(AliquotError::OverflowError(err_msg));
+        }
+        Ok(a + b)
+    }
+
+    /// Returns k, if n is a multiply-perfect number with sigma(n) = k * n.
+    /// Perfect numbers have the index two. Returns None for all other numbers.
+    pub fn multiperfect_index(&mut self, n: T) -> Result<Option<u32>, AliquotError> {
+        if n == T::ZERO {
+            return Ok(None);
+        }
+        let sigma = Self::add_checked(self.next_term(n)?, n)?;
+        let k = sigma / n;
+        if sigma % n != T::ZERO || k < T::TWO {
+            return Ok(None);
+        }
+        Ok(k.try_into().ok().and_then(|k| u32::try_from(k).ok()))
+    }
+
+    /// Returns all even perfect numbers up to bound in ascending order. These are
+    /// generated from the Mersenne primes 2^p - 1 found by the Lucas-Lehmer
+    /// test as 2^(p - 1) * (2^p - 1) instead of scanning all numbers. Odd
+    /// perfect numbers are not found, but none is known to exist.
+    pub fn perfect_numbers_up_to(bound: T) -> Vec<T> {
+        let mut perfect = vec![];
+        // Perfect numbers of exponents beyond 64 exceed the maximum of u128
+        for p in 2u32..65 {
+            if !(2..p).take_while(|d| d * d <= p).all(|d| p % d != 0) {
+                continue;
+            }
+            let mersenne = (1u128 << p) - 1;
+            let Some(n) = (1u128 << (p - 1)).checked_mul(mersenne) else {
+                break;
+            };
+            if n > bound.to_u128() {
+                break;
+            }
+            if Self::lucas_lehmer(p)
+                && let Some(n) = T::from_u128(n)
+            {
+                perfect.push(n);
+            }
+        }
+        perfect
+    }
+
+    /// Returns true, if the Mersenne number 2^p - 1 of the prime p is prime.
+    fn lucas_lehmer(p: u32) -> bool {
+        if p == 2 {
+            return true;
+        }
+        // The Mersenne number is below 2^64 and its square fits into u128
+        let mersenne = (1u128 << p) - 1;
+        let mut s = 4u128;
+        for _ in 0..(p - 2) {
+            s = (s * s + mersenne - 2) % mersenne;
+        }
+        s == 0
+    }
+
+    /// Returns the abundancy index sigma(n) / n. Perfect numbers have the index
+    /// two, abundant numbers a larger and deficient numbers a smaller one.
+    pub fn abundancy(&mut self, n: T) -> Result<f64, AliquotError> {
+        if n == T::ZERO {
+            let err_msg = "Abundancy of zero is undefined".to_string();
+            return Err(AliquotError::InvalidArg(err_msg));
+        }
+        let sigma = Self::add_checked(self.next_term(n)?, n)?;
+        Ok(sigma.to_f64() / n.to_f64())
+    }
+
+    /// Returns true, if a subset of the proper divisors of n sums up to n.
+    /// This includes all perfect numbers, but no deficient numbers. Instead of
+    /// n the complementary subset summing up to the excess of the aliquot sum
+    /// over n is searched, which is much smaller. Excesses too large for the
+    /// table of reachable sums result in an error.
+    pub fn is_semiperfect(&mut self, n: T) -> Result<bool, AliquotError> {
+        const MAX_EXCESS: usize = 1 << 26;
+        if n == T::ZERO {
+            return Ok(false);
+        }
+        let sum = self.next_term(n)?;
+        if sum < n {
+            return Ok(false);
+        } else if sum == n {
+            return Ok(true);
+        }
+        let target = Self::offset(sum - n, T::ZERO)?;
+        if target > MAX_EXCESS {
+            let err_msg = format!(
+                "The excess {} of {n} is too large for computing subset sums",
+                sum - n
+            );
+            return Err(AliquotError::InvalidArg(err_msg));
+        }
+        // Mark all sums up to the excess, which can be reached by a subset of the divisors
+        let mut reachable = vec![false; target + 1];
+        reachable[0] = true;
+        for d in Self::proper_divisors(n) {
+            let Ok(d) = Self::offset(d, T::ZERO) else {
+                break;
+            };
+            if d > target {
+                // The divisors are sorted, so all remaining ones exceed the excess
+                break;
+            }
+            for j in (d..=target).rev() {
+                if reachable[j - d] {
+                    reachable[j] = true;
+                }
+            }
+            if reachable[target] {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns true, if n is a prime number.
+    pub fn is_prime(&mut self, n: T) -> Result<bool, AliquotError> {
+        if n <= T::ONE {
+            return Ok(false);
+        }
+        Ok(self.next_term(n)? == T::ONE)
+    }
+
+    /// Returns true, if n is a perfect number. Even numbers are perfect, if they
+    /// are of the form 2^(p - 1) * (2^p - 1) with a Mersenne prime 2^p - 1. This
+    /// is checked by the Lucas-Lehmer test, so the divisors of huge numbers are
+    /// not summed up and abundant numbers don't overflow.
+    pub fn is_perfect(&mut self, n: T) -> Result<bool, AliquotError> {
+        if n == T::ZERO {
+            return Ok(false);
+        }
+        let m = n.to_u128();
+        if m.is_multiple_of(2) {
+            let p = m.trailing_zeros() + 1;
+            // Perfect numbers beyond the exponent 64 exceed the maximum of u128
+            return Ok(p <= 64 && m >> (p - 1) == (1u128 << p) - 1 && Self::lucas_lehmer(p));
+        }
+        Ok(self.next_term(n)? == n)
+    }
+
+    /// Returns true, if n is a member of an aliquot cycle of the given order.
+    /// Order one means perfect, two amicable and three or more sociable. Numbers
+    /// only running into a cycle are not members of it.
+    pub fn is_sociable(&mut self, n: T, order: usize) -> Result<bool, AliquotError> {
+        if order == 0 {
+            return Err(AliquotError::InvalidArg(
+                "Order must be at least 1".to_string(),
+            ));
+        }
+        let aliquot_seq = self.aliquot_seq(n);
+        match &aliquot_seq {
+            AliquotSeq::PerfectNumber(_) => Ok(order == 1),
+            AliquotSeq::AmicableNumber(_) | AliquotSeq::SociableNumber(_) => {
+                Ok(aliquot_seq.cycle_length() == Some(order))
+            }
+            AliquotSeq::Unknown(_, message) if message.reason == UnknownReason::Arithmetic => {
+                Err(AliquotError::OverflowError(message.text.clone()))
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Determines the fate of the sequence of n using at most a few steps of
+    /// the sequence and the cache instead of computing the whole sequence.
+    /// Sequences without a known fate after these steps are open.
+    pub fn quick_fate(&mut self, n: T) -> Result<Fate, AliquotError> {
+        const MAX_STEPS: usize = 32;
+        if n <= T::ONE {
+            return Ok(Fate::Open);
+        }
+        if self.is_prime(n)? {
+            return Ok(Fate::Terminates);
+        }
+        if self.is_perfect(n)? {
+            return Ok(Fate::Perfect);
+        }
+        let mut seq = vec![n];
+        let mut last = n;
+        for _ in 0..MAX_STEPS {
+            let next = self.next_term(last)?;
+            if next >= self.max_num {
+                return Ok(Fate::Open);
+            } else if next == T::ONE || next == self.terminal {
+                return Ok(Fate::Terminates);
+            } else if let Some(aliquot_seq) = self.cache.get(next) {
+                // The sequence of n continues with the cached sequence
+                return Ok(aliquot_seq.fate());
+            } else if next == last {
+                return Ok(Fate::Perfect);
+            } else if seq.contains(&next) {
+                return Ok(Fate::Cycle);
+            }
+            seq.push(next);
+            last = next;
+        }
+        Ok(Fate::Open)
+    }
+
+    /// Returns true, if the aliquot sequence of n is or runs into a cycle of
+    /// amicable or sociable numbers. Unlike aliquot_seq, the terms are not stored,
+    /// since Floyd's cycle detection needs constant memory only. Perfect numbers
+    /// don't count as cycles. Returns None, if no decision has been made within
+    /// max_steps steps.
+    pub fn detects_cycle(&mut self, n: T, max_steps: usize) -> Result<Option<bool>, AliquotError> {
+        let mut slow = n;
+        let mut fast = n;
+        for _ in 0..max_steps {
+            slow = self.next_term(slow)?;
+            fast = self.next_term(self.next_term(fast)?)?;
+            if slow == fast {
+                // Terminating sequences meet at zero, which is a fixed point like
+                // perfect numbers
+                return Ok(Some(self.next_term(slow)? != slow));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns whether n is deficient, perfect or abundant.
+    pub fn classify_number(&mut self, n: T) -> Result<NumberClass, AliquotError> {
+        let sum = self.next_term(n)?;
+        Ok(if sum < n {
+            NumberClass::Deficient
+        } else if sum == n {
+            NumberClass::Perfect
+        } else {
+            NumberClass::Abundant
+        })
+    }
+
+    /// Returns the class of n together with its distance from perfection, which
+    /// is the excess s(n) - n of abundant and the deficiency n - s(n) of
+    /// deficient numbers. The distance of perfect numbers is zero.
+    pub fn aliquot_residue(&mut self, n: T) -> Result<(NumberClass, T), AliquotError> {
+        if n == T::ZERO {
+            let err_msg = "Aliquot residue of zero is undefined".to_string();
+            return Err(AliquotError::InvalidArg(err_msg));
+        }
+        let sum = self.next_term(n)?;
+        Ok(if sum < n {
+            (NumberClass::Deficient, n - sum)
+        } else if sum == n {
+            (NumberClass::Perfect, T::ZERO)
+        } else {
+            (NumberClass::Abundant, sum - n)
+        })
+    }
+
+    /// Checks the aliquot sequence of n against a reference derivation using
+    /// trial division: The sequence must start with n, every term must be the
+    /// aliquot sum of its predecessor and the type must match the terms, e.g.
+    /// cycles must close. Returns an error describing the first violation.
+    pub fn verify(&self, n: T, aliquot_seq: &AliquotSeq<T>) -> Result<(), AliquotError> {
+        let invalid =
+            |msg: String| Err(AliquotError::InvalidArg(format!("Sequence of {n}: {msg}")));
+        let seq = aliquot_seq.seq();
+        if seq[0] != n {
+            return invalid(format!("Starts with {}", seq[0]));
+        }
+        for w in seq.windows(2) {
+            let sum = Self::aliquot_sum(w[0])?;
+            if sum != w[1] {
+                return invalid(format!("{} follows {}, but its sum is {sum}", w[1], w[0]));
+            }
+        }
+        let last = seq[seq.len() - 1];
+        // Checks that the cycle closes and contains every number only once
+        let check_cycle = |cycle: &[T]| -> Result<(), AliquotError> {
+            if Self::aliquot_sum(cycle[cycle.len() - 1])? != cycle[0] {
+                return invalid("Cycle does not close".to_string());
+            }
+            if cycle.iter().collect::<HashSet<&T>>().len() != cycle.len() {
+                return invalid("Cycle contains a number twice".to_string());
+            }
+            Ok(())
+        };
+        match aliquot_seq {
+            AliquotSeq::PerfectNumber(_) => {
+                if Self::aliquot_sum(n)? != n {
+                    return invalid("Not a perfect number".to_string());
+                }
+            }
+            AliquotSeq::PrimeNumber(_) | AliquotSeq::Convergent(_) => {
+                if last != T::ONE && last != self.terminal {
+                    return invalid(format!("Ends with {last}"));
+                }
+            }
+            AliquotSeq::AmicableNumber(_) => check_cycle(&seq)?,
+            AliquotSeq::SociableNumber(cycle) => {
+                if cycle.len() < 3 {
+                    return invalid(format!("Cycle of only {} numbers", cycle.len()));
+                }
+                check_cycle(cycle)?;
+            }
+            AliquotSeq::AspiringNumber(_) => {
+                if seq.len() < 2 || Self::aliquot_sum(last)? != last {
+                    return invalid(format!("Does not end with a perfect number after {n}"));
+                }
+            }
+            AliquotSeq::IntoCycle(v0, cycle) => {
+                if v0.is_empty() || cycle.is_empty() || v0.iter().any(|t| cycle.contains(t)) {
+                    return invalid("Does not run into a separate cycle".to_string());
+                }
+                check_cycle(cycle)?;
+            }
+            AliquotSeq::Unknown(_, _) => {}
+        }
+        Ok(())
+    }
+
+    /// Computes the aliquot sum of n using the sieve, if n is within its limit.
+    fn next_term(&self, n: T) -> Result<T, AliquotError> {
+        if let Some(sieve) = &self.sieve {
+            let sum = n
+                .try_into()
+                .ok()
+                .and_then(|k| sieve.aliquot_sum(k))
+                .and_then(|sum| T::try_from(sum).ok());
+            if let Some(sum) = sum {
+                return Ok(sum);
+            }
+        }
+        Self::aliquot_sum(n)
+    }
+
+    /// Sums up all proper divisors for every number in the range using a single
+    /// sieve pass instead of trial division per number. The sums are returned in
+    /// the order of the range.
+    pub fn aliquot_sum_sieve(range: Range<T>) -> Result<Vec<T>, AliquotError> {
+        let sums = Self::sieve_sums(range.clone());
+        iter_range(range)
+            .zip(sums)
+            .map(|(n, sum)| match sum {
+                Some(sum) => Ok(sum),
+                // Let trial division report the overflow
+                None => Self::aliquot_sum(n),
+            })
+            .collect()
+    }
+
+    /// Returns the aliquot sums of all consecutive numbers in the range. A linear
+    /// sieve of smallest prime factors is extended number by number and the sum
+    /// of divisors of every number is derived from the sum of a smaller number,
+    /// since it is multiplicative. The linear sieve covers all numbers from zero,
+    /// so it is only used below LINEAR_LEN. Larger numbers are sieved in
+    /// segments relative to their start.
+    pub fn aliquot_sum_stream(
+        &mut self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (T, Result<T, AliquotError>)> {
+        const LINEAR_LEN: u64 = 1 << 20;
+        const SEGMENT_LEN: u64 = 1 << 16;
+        let linear_end = T::try_from(LINEAR_LEN)
+            .unwrap_or(T::MAX)
+            .min(range.end)
+            .max(range.start);
+        // The linear sieve is not allocated for ranges starting beyond it
+        let len = if range.start < linear_end {
+            linear_end.to_u128() as usize
+        } else {
+            0
+        };
+        // Smallest prime factor, its largest power dividing the number and
+        // the sum of all divisors of every number processed so far
+        let mut spf = vec![0usize; len];
+        let mut pw = vec![0usize; len];
+        let mut sigma = vec![0u128; len];
+        let mut primes = vec![];
+        let mut next = 2;
+        let linear = iter_range(range.start..linear_end).map(move |n| {
+            let k = n.to_u128() as usize;
+            if k <= 1 {
+                return (n, Ok(T::ZERO));
+            }
+            while next <= k {
+                let i = next;
+                if spf[i] == 0 {
+                    spf[i] = i;
+                    pw[i] = i;
+                    sigma[i] = 1 + i as u128;
+                    primes.push(i);
+                }
+                for &p in primes.iter() {
+                    let j = i * p;
+                    if p > spf[i] || j >= len {
+                        break;
+                    }
+                    spf[j] = p;
+                    if p == spf[i] {
+                        pw[j] = pw[i] * p;
+                        sigma[j] = if pw[j] == j {
+                            sigma[i] + j as u128
+                        } else {
+                            sigma[j / pw[j]] * sigma[pw[j]]
+                        };
+                    } else {
+                        pw[j] = p;
+                        sigma[j] = sigma[i] * (1 + p as u128);
+                    }
+                }
+                next += 1;
+            }
+            let sum = T::from_u128(sigma[k] - k as u128).ok_or_else(|| {
+                let err_msg = format!("Aliquot sum of {n} exceeds maximum {}", T::MAX);
+                AliquotError::OverflowError(err_msg)
+            });
+            (n, sum)
+        });
+        let seg_len = T::try_from(SEGMENT_LEN).unwrap_or(T::MAX);
+        let end = range.end;
+        let mut start = linear_end;
+        let segmented = std::iter::from_fn(move || {
+            if start >= end {
+                return None;
+            }
+            let seg_end = if (end - start) > seg_len {
+                start + seg_len
+            } else {
+                end
+            };
+            let segment = start..seg_end;
+            start = seg_end;
+            let sums = Self::sieve_sums(segment.clone());
+            Some(iter_range(segment).zip(sums).map(|(n, sum)| match sum {
+                Some(sum) => (n, Ok(sum)),
+                // Let trial division report the overflow
+                None => (n, Self::aliquot_sum(n)),
+            }))
+        })
+        .flatten();
+        linear.chain(segmented)
+    }
+
+    /// Sums up all proper divisors for every number in the range using a sieve.
+    /// Sums exceeding the maximum value of T are None.
+    fn sieve_sums(range: Range<T>) -> Vec<Option<T>> {
+        let start = range.start;
+        let end = range.end;
+        let mut sums = iter_range(range)
+            .map(|n| Some(if n <= T::ONE { T::ZERO } else { T::ONE }))
+            .collect::<Vec<Option<T>>>();
+        if sums.is_empty() {
+            return sums;
+        }
+        let last = end - T::ONE;
+        // Every divisor d with d * d <= n is added together with its cofactor q
+        let mut d = T::TWO;
+        while d <= last / d {
+            let mut q = start / d;
+            if q * d < start {
+                q += T::ONE;
+            }
+            if q < d {
+                q = d;
+            }
+            while q <= last / d {
+                let add = if q != d { d + q } else { d };
+                // All offsets are below the length of the sums, which fits into usize
+                if let Ok(ind) = Self::offset(q * d, start)
+                    && let Some(sum) = sums.get_mut(ind)
+                {
+                    *sum = sum
+                        .filter(|&sum| add <= (T::MAX - sum))
+                        .map(|sum| sum + add);
+                }
+                q += T::ONE;
+            }
+            d += T::ONE;
+        }
+        sums
+    }
+
+    /// Returns the set of all abundant numbers in the range, whose aliquot sum
+    /// exceeds the number itself. The sums are computed by a single sieve pass.
+    pub fn abundant_bitset(range: Range<T>) -> BitSet<T> {
+        let mut bitset = BitSet::new(range.clone());
+        let sums = Self::sieve_sums(range.clone());
+        for (n, sum) in iter_range(range).zip(sums) {
+            // Sums exceeding the maximum of T are larger than n anyway
+            if sum.is_none_or(|sum| sum > n) {
+                bitset.insert(n);
+            }
+        }
+        bitset
+    }
+
+    /// Splits the range into at most n_chunks consecutive ranges of roughly equal
+    /// cost. Trial division of n costs about sqrt(n), so the total cost up to x
+    /// grows with x^1.5 and chunks of large numbers are smaller. Empty chunks are
+    /// left out.
+    pub fn balanced_chunks(range: Range<T>, n_chunks: usize) -> Vec<Range<T>> {
+        if range.end <= range.start || n_chunks == 0 {
+            return vec![];
+        }
+        let (first, last) = (range.start.to_f64(), range.end.to_f64());
+        let (cost_start, cost_end) = (first.powf(1.5), last.powf(1.5));
+        let width = (range.end - range.start).to_u128();
+        let mut chunks = vec![];
+        let mut start = range.start;
+        for i in 1..=n_chunks {
+            let share = i as f64 / n_chunks as f64;
+            // The costs of huge numbers close to each other can't be told apart
+            let fraction = if cost_end > cost_start {
+                ((cost_start + (cost_end - cost_start) * share).powf(2.0 / 3.0) - first)
+                    / (last - first)
+            } else {
+                share
+            };
+            let offset = (width as f64 * fraction.clamp(0.0, 1.0)).round() as u128;
+            let end = match T::from_u128(offset.min(width)) {
+                Some(offset) if i < n_chunks => range.start + offset,
+                _ => range.end,
+            };
+            if end > start {
+                chunks.push(start..end);
+                start = end;
+            }
+        }
+        chunks
+    }
+
+    /// Returns the numbers of abundant, deficient and perfect numbers in the
+    /// range. The sums are computed by a single sieve pass.
+    pub fn classify_census(range: Range<T>) -> (usize, usize, usize) {
+        let (mut abundant, mut deficient, mut perfect) = (0, 0, 0);
+        let sums = Self::sieve_sums(range.clone());
+        for (n, sum) in iter_range(range).zip(sums) {
+            match sum {
+                Some(sum) if sum < n => deficient += 1,
+                Some(sum) if sum == n => perfect += 1,
+                // Sums exceeding the maximum of T are larger than n anyway
+                _ => abundant += 1,
+            }
+        }
+        (abundant, deficient, perfect)
+    }
+
+    /// Returns the pairs (n, s(n) - n) of all abundant and perfect numbers in the
+    /// range sorted by this abundance in descending order. Numbers of equal
+    /// abundance are sorted in ascending order. Deficient numbers have no
+    /// abundance and are left out like numbers, whose sums exceed the maximum
+    /// of T. The sums are computed by a single sieve pass.
+    pub fn by_abundance(&mut self, range: Range<T>) -> Vec<(T, T)> {
+        let sums = Self::sieve_sums(range.clone());
+        let mut abundances = iter_range(range)
+            .zip(sums)
+            .filter_map(|(n, sum)| match sum {
+                Some(sum) if n > T::ZERO && sum >= n => Some((n, sum - n)),
+                _ => None,
+            })
+            .collect::<Vec<(T, T)>>();
+        abundances.sort_by(|(n0, a0), (n1, a1)| a1.cmp(a0).then(n0.cmp(n1)));
+        abundances
+    }
+
+    /// Sums up all proper divisors for every number in the slice. The numbers are
+    /// sorted internally and sieved in segments covering the span of the numbers.
+    /// Numbers without any other number in their segment are factorized
+    /// directly. The sums are returned in the original order of the numbers.
+    pub fn aliquot_sum_batch(ns: &[T]) -> Result<Vec<T>, AliquotError> {
+        const SEGMENT_LEN: u64 = 1 << 16;
+        let seg_len = T::try_from(SEGMENT_LEN).unwrap_or(T::MAX);
+        let mut order = (0..ns.len()).collect::<Vec<usize>>();
+        order.sort_by_key(|&i| ns[i]);
+        let mut sums = vec![T::ZERO; ns.len()];
+        let mut pos = 0;
+        while pos < order.len() {
+            // Each segment starts at the smallest number not computed yet
+            let start = ns[order[pos]];
+            let limit = if (T::MAX - start) > seg_len {
+                start + seg_len
+            } else {
+                T::MAX
+            };
+            let mut last = pos;
+            while last + 1 < order.len() && ns[order[last + 1]] < limit {
+                last += 1;
+            }
+            if last == pos {
+                // Sieving a whole segment doesn't pay off for a single number
+                sums[order[pos]] = Self::aliquot_sum(start)?;
+                pos += 1;
+                continue;
+            }
+            // The segment ends right after the largest number it contains
+            let end = ns[order[last]] + T::ONE;
+            let seg_sums = Self::sieve_sums(start..end);
+            for &i in &order[pos..=last] {
+                sums[i] = match seg_sums[Self::offset(ns[i], start)?] {
+                    Some(sum) => sum,
+                    None => Self::aliquot_sum(ns[i])?,
+                };
+            }
+            pos = last + 1;
+        }
+        Ok(sums)
+    }
+
+    /// Calls f for every number m up to and including bound, whose aliquot sum
+    /// equals n, in ascending order. Stops as soon as f returns false.
+    fn visit_preimages(n: T, bound: T, mut f: impl FnMut(T) -> bool) {
+        const SEGMENT_LEN: u64 = 1 << 16;
+        let seg_len = T::try_from(SEGMENT_LEN).unwrap_or(T::MAX);
+        let mut start = T::ONE;
+        while start <= bound {
+            let end = if (bound - start) >= seg_len {
+                start + seg_len
+            } else {
+                bound
+            };
+            let sums = Self::sieve_sums(start..end);
+            for (m, sum) in iter_range(start..end).zip(sums) {
+                if sum == Some(n) && !f(m) {
+                    return;
+                }
+            }
+            if end == bound {
+                // The bound itself is not contained in the segment
+                if Self::aliquot_sum(bound).ok() == Some(n) {
+                    f(bound);
+                }
+                return;
+            }
+            start = end;
+        }
+    }
+
+    /// Returns a bound, which no preimage of n exceeds. A composite number m has
+    /// a divisor d <= sqrt(m), so its aliquot sum is at least 1 + m / d >= 1 +
+    /// sqrt(m) and every composite preimage of n is at most (n - 1)^2. Primes are
+    /// the preimages of one, which has infinitely many preimages, so None is
+    /// returned for one. Bounds exceeding the maximum of T are clamped.
+    pub fn safe_preimage_bound(n: T) -> Option<T> {
+        if n == T::ZERO {
+            // Only the aliquot sum of one is zero
+            return Some(T::ONE);
+        }
+        if n == T::ONE {
+            return None;
+        }
+        let root = n - T::ONE;
+        if root > T::MAX / root {
+            Some(T::MAX)
+        } else {
+            Some(root * root)
+        }
+    }
+
+    /// Returns all numbers up to and including bound, whose aliquot sum equals n.
+    /// Without a bound all preimages up to safe_preimage_bound are returned. The
+    /// aliquot sums of all numbers up to the bound are computed, so the cost grows
+    /// with (n - 1)^2 and an automatic bound is only used up to 2^30, which
+    /// covers n up to 32769. Beyond that and for one, whose preimages are all
+    /// primes, an error is returned and the bound has to be given explicitly.
+    pub fn preimages(&mut self, n: T, bound: Option<T>) -> Result<Vec<T>, AliquotError> {
+        const MAX_AUTO_BOUND: u128 = 1 << 30;
+        let bound = match bound {
+            Some(bound) => bound,
+            None => match Self::safe_preimage_bound(n) {
+                Some(bound) if bound.to_u128() <= MAX_AUTO_BOUND => bound,
+                Some(bound) => {
+                    return Err(AliquotError::InvalidArg(format!(
+                        "The preimages of {n} would be searched up to {bound}, use Some(bound) to search them up to a smaller bound"
+                    )));
+                }
+                None => {
+                    return Err(AliquotError::InvalidArg(format!(
+                        "{n} has infinitely many preimages, a bound is required"
+                    )));
+                }
+            },
+        };
+        let mut preimages = vec![];
+        Self::visit_preimages(n, bound, |m| {
+            preimages.push(m);
+            true
+        });
+        Ok(preimages)
+    }
+
+    /// Follows the smallest preimage up to and including bound back from n, until
+    /// a number without preimages is reached or the chain contains max_depth
+    /// predecessors. The chain starts with n and never contains a number twice.
+    pub fn trace_back(&mut self, n: T, bound: T, max_depth: usize) -> Vec<T> {
+        let mut chain = vec![n];
+        while chain.len() <= max_depth {
+            let last = chain[chain.len() - 1];
+            let mut pred = None;
+            Self::visit_preimages(last, bound, |m| {
+                if chain.contains(&m) {
+                    return true;
+                }
+                pred = Some(m);
+                false
+            });
+            match pred {
+                Some(m) => chain.push(m),
+                None => break,
+            }
+        }
+        chain
+    }
+
+    /// Returns the number of numbers up to and including bound, whose aliquot
+    /// sum equals n. Untouchable numbers have no preimages at all.
+    pub fn in_degree(&mut self, n: T, bound: T) -> usize {
+        let mut count = 0;
+        Self::visit_preimages(n, bound, |_| {
+            count += 1;
+            true
+        });
+        count
+    }
+
+    /// Returns the smallest number greater than from without any preimage up to
+    /// and including search_bound, i.e. the next untouchable number with respect
+    /// to the bound. The search stops at the first one found. None is returned,
+    /// if no such number fits into T.
+    pub fn next_untouchable(&mut self, from: T, search_bound: T) -> Option<T> {
+        let mut n = from;
+        while n < T::MAX {
+            n += T::ONE;
+            let mut touched = false;
+            Self::visit_preimages(n, search_bound, |_| {
+                touched = true;
+                false
+            });
+            if !touched {
+                return Some(n);
+            }
+        }
+        None
+    }
+
+    /// Returns the offset of n from start as an index or an error, if the
+    /// offset doesn't fit into usize.
+    fn offset(n: T, start: T) -> Result<usize, AliquotError> {
+        let err = || {
+            AliquotError::conversion(format!(
+                "Offset of {n} from {start} exceeds the maximum index"
+            ))
+        };
+        let ind: u64 = (n - start).try_into().map_err(|_| err())?;
+        usize::try_from(ind).map_err(|_| err())
+    }
+
+    /// Computes the aliquot sequence of a number n.
+    pub fn aliquot_seq(&mut self, n: T) -> AliquotSeq<T> {
+        let aliquot_seq = self.aliquot_seq_from(n, None, None, None);
+        self.add_to_stats(&aliquot_seq);
+        aliquot_seq
+    }
+
+    /// Computes the aliquot sequence of a number n, but stops as soon as the flag
+    /// is set. The terms computed so far are returned as an unknown sequence.
+    pub fn aliquot_seq_interruptible(&mut self, n: T, stop: &AtomicBool) -> AliquotSeq<T> {
+        let aliquot_seq = self.aliquot_seq_from(n, None, None, Some(stop));
+        self.add_to_stats(&aliquot_seq);
+        aliquot_seq
+    }
+
+    /// Counts the type of the sequence, if a stats handle has been set.
+    fn add_to_stats(&self, aliquot_seq: &AliquotSeq<T>) {
+        if let Some(stats) = &self.stats {
+            stats.add(aliquot_seq);
+        }
+    }
+
+    /// Computes the sequence of a number n using a custom step function instead
+    /// of the aliquot sum. The cache is not used, since it only contains
+    /// sequences computed with the aliquot sum.
+    pub fn aliquot_seq_with<S: StepFn<T>>(&mut self, n: T, step: &S) -> AliquotSeq<T> {
+        self.aliquot_seq_uncached(n, step)
+    }
+
+    /// Computes the sequence of a number n using the step function without the cache.
+    fn aliquot_seq_uncached(&mut self, n: T, step: &dyn StepFn<T>) -> AliquotSeq<T> {
+        let cache = std::mem::replace(&mut self.cache, Cache::new(0));
+        let aliquot_seq = self.aliquot_seq_from(n, None, Some(step), None);
+        self.cache = cache;
+        aliquot_seq
+    }
+
+    /// Computes the aliquot sequences of all numbers in the range. The first
+    /// aliquot sum of every number is taken from a sieve over the range, so only
+    /// the following terms need to be computed by trial division.
+    pub fn scan(&mut self, range: Range<T>) -> impl Iterator<Item = (T, AliquotSeq<T>)> {
+        // Sums exceeding the maximum of T are None and computed by trial division
+        let sums = Self::sieve_sums(range.clone());
+        iter_range(range).zip(sums).map(move |(n, first)| {
+            let aliquot_seq = self.aliquot_seq_from(n, first, None, None);
+            self.add_to_stats(&aliquot_seq);
+            (n, aliquot_seq)
+        })
+    }
+
+    /// Computes the aliquot sequences of a list of numbers and returns them in
+    /// the order of the list. The numbers are computed in ascending order, so
+    /// the cached sequences of small numbers complete the sequences of larger ones.
+    pub fn aliquot_seqs(&mut self, ns: &[T]) -> Vec<AliquotSeq<T>> {
+        let mut order = (0..ns.len()).collect::<Vec<usize>>();
+        order.sort_by_key(|&a| ns[a]);
+        let mut aliquot_seqs = vec![None; ns.len()];
+        for ind in order {
+            aliquot_seqs[ind] = Some(self.aliquot_seq(ns[ind]));
+        }
+        aliquot_seqs.into_iter().flatten().collect()
+    }
+
+    /// Computes the aliquot sequences of all numbers in the range and returns
+    /// them mapped by their numbers for random access. The cache is used and
+    /// filled as by aliquot_seq.
+    pub fn aliquot_seq_map(&mut self, range: Range<T>) -> HashMap<T, AliquotSeq<T>> {
+        iter_range(range)
+            .map(|n| (n, self.aliquot_seq(n)))
+            .collect()
+    }
+
+    /// Computes the aliquot sequences of all numbers in the range on a blocking
+    /// thread of the tokio runtime and yields the results as a stream. The
+    /// generator is moved to the thread, so its cache is kept for the whole range.
+    #[cfg(feature = "tokio")]
+    pub fn aliquot_stream(
+        mut self,
+        range: Range<T>,
+    ) -> impl tokio_stream::Stream<Item = (T, AliquotSeq<T>)>
+    where
+        T: Send + 'static,
+    {
+        let (sender, receiver) = tokio::sync::mpsc::channel(64);
+        tokio::task::spawn_blocking(move || {
+            for n in iter_range(range) {
+                let aliquot_seq = self.aliquot_seq(n);
+                // Stop computing, if the stream has been dropped
+                if sender.blocking_send((n, aliquot_seq)).is_err() {
+                    break;
+                }
+            }
+        });
+        tokio_stream::wrappers::ReceiverStream::new(receiver)
+    }
+
+    /// Returns the fates of the sequences of all numbers in the range computed
+    /// with two different step functions.
+    pub fn compare_maps(
+        &mut self,
+        range: Range<T>,
+        step_a: &dyn StepFn<T>,
+        step_b: &dyn StepFn<T>,
+    ) -> Vec<(T, Fate, Fate)> {
+        iter_range(range)
+            .map(|n| {
+                let fate_a = self.aliquot_seq_uncached(n, step_a).fate();
+                let fate_b = self.aliquot_seq_uncached(n, step_b).fate();
+                (n, fate_a, fate_b)
+            })
+            .collect()
+    }
+
+    /// Classifies all numbers in the range in parallel. The range is split into
+    /// chunks, each processed by a forked generator. The classes are returned in
+    /// the order of the range.
+    #[cfg(feature = "rayon")]
+    pub fn classify_range(&self, range: Range<T>) -> Result<Vec<NumberClass>, AliquotError>
+    where
+        T: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        const CHUNK_LEN: u64 = 1 << 12;
+        let chunk_len = T::try_from(CHUNK_LEN).unwrap_or(T::MAX);
+        let mut chunks = vec![];
+        let mut start = range.start;
+        while start < range.end {
+            let end = if (range.end - start) > chunk_len {
+                start + chunk_len
+            } else {
+                range.end
+            };
+            chunks.push(start..end);
+            start = end;
+        }
+        let classes = chunks
+            .into_par_iter()
+            .map(|chunk| {
+                let mut gener = self.fork();
+                iter_range(chunk)
+                    .map(|n| gener.classify_number(n))
+                    .collect::<Result<Vec<NumberClass>, AliquotError>>()
+            })
+            .collect::<Result<Vec<Vec<NumberClass>>, AliquotError>>()?;
+        Ok(classes.into_iter().flatten().collect())
+    }
+
+    /// Returns the number with the longest aliquot sequence in the range together
+    /// with its sequence. Ties are broken by the smallest number.
+    pub fn longest_in_range(&mut self, range: Range<T>) -> Option<(T, AliquotSeq<T>)> {
+        let mut longest: Option<(T, AliquotSeq<T>)> = None;
+        for n in iter_range(range) {
+            let aliquot_seq = self.aliquot_seq(n);
+            if longest
+                .as_ref()
+                .is_none_or(|(_, l)| aliquot_seq.len() > l.len())
+            {
+                longest = Some((n, aliquot_seq));
+            }
+        }
+        longest
+    }
+
+    /// Returns the k numbers with the longest aliquot sequences in the range
+    /// together with their lengths sorted by length in descending order. Ties
+    /// are broken by the smallest number. Only k results are kept at a time in a
+    /// min-heap, so the memory doesn't depend on the size of the range.
+    pub fn top_k_longest(&mut self, range: Range<T>, k: usize) -> Vec<(T, usize)> {
+        if k == 0 {
+            return vec![];
+        }
+        // The shortest sequence and the largest number of equal length are on top
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        for n in iter_range(range) {
+            let len = self.aliquot_seq(n).len();
+            heap.push(Reverse((len, Reverse(n.to_u128()))));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        heap.into_sorted_vec()
+            .into_iter()
+            .filter_map(|Reverse((len, Reverse(n)))| Some((T::from_u128(n)?, len)))
+            .collect()
+    }
+
+    /// Returns true, if the aliquot sequences of a and b have a number in common,
+    /// i.e. one leads to the other or both merge. Since almost all terminating
+    /// sequences end with one, one doesn't count as a common number.
+    pub fn same_trajectory(&mut self, a: T, b: T) -> bool {
+        let terms_a = self
+            .aliquot_seq(a)
+            .seq()
+            .into_iter()
+            .filter(|&t| t > T::ONE)
+            .collect::<HashSet<T>>();
+        self.aliquot_seq(b)
+            .seq()
+            .into_iter()
+            .any(|t| terms_a.contains(&t))
+    }
+
+    /// Returns all pairs (a, b) with a < b and a in the range, where applying
+    /// the aliquot sum k times to a yields b and k times to b yields a. For
+    /// k = 1 these are the amicable pairs.
+    pub fn k_step_amicable(&mut self, range: Range<T>, k: usize) -> Vec<(T, T)> {
+        let iterate = |n: T| -> Option<T> {
+            let mut t = n;
+            for _ in 0..k {
+                t = self.next_term(t).ok()?;
+            }
+            Some(t)
+        };
+        iter_range(range)
+            .filter_map(|a| {
+                let b = iterate(a)?;
+                (b > a && iterate(b)? == a).then_some((a, b))
+            })
+            .collect()
+    }
+
+    /// Returns all amicable pairs (a, b) with a < b and a in the range.
+    pub fn amicable_pairs(&mut self, range: Range<T>) -> Vec<(T, T)> {
+        self.k_step_amicable(range, 1)
+    }
+
+    /// Returns all amicable pairs (a, b) with a < b and a in the range, where
+    /// both members satisfy the predicate, e.g. both are even.
+    pub fn amicable_pairs_filtered(
+        &mut self,
+        range: Range<T>,
+        predicate: impl Fn(T) -> bool,
+    ) -> Vec<(T, T)> {
+        self.amicable_pairs(range)
+            .into_iter()
+            .filter(|&(a, b)| predicate(a) && predicate(b))
+            .collect()
+    }
+
+    /// Returns the smallest number in the range, whose aliquot sequence reaches
+    /// a number exceeding bound before it terminates or cycles. These numbers
+    /// are candidates for open sequences like 276. The sequences are computed
+    /// step by step and abandoned as soon as they exceed the bound.
+    pub fn first_escaping(&mut self, range: Range<T>, bound: T) -> Option<T> {
+        iter_range(range).find(|&n| self.escapes(n, bound))
+    }
+
+    /// Returns true, if the aliquot sequence of n reaches a number exceeding bound.
+    fn escapes(&self, n: T, bound: T) -> bool {
+        let mut seen = HashSet::<T>::new();
+        let mut t = n;
+        while t <= bound {
+            if t <= T::ONE || !seen.insert(t) || seen.len() > self.max_len_seq {
+                return false;
+            }
+            // The rest of a cached sequence doesn't need to be computed
+            if let Some(aliquot_seq) = self.cache.get(t) {
+                return aliquot_seq.seq().into_iter().any(|x| x > bound);
+            }
+            t = match self.next_term(t) {
+                Ok(next) => next,
+                // The sum exceeds the maximum of T and therefore the bound
+                Err(_) => return true,
+            };
+        }
+        true
+    }
+
+    /// Returns the number of distinct numbers contained in the aliquot sequences
+    /// of all numbers in the range.
+    pub fn coverage(&mut self, range: Range<T>) -> usize {
+        let mut covered = HashSet::<T>::new();
+        for n in iter_range(range) {
+            covered.extend(self.aliquot_seq(n).seq());
+        }
+        covered.len()
+    }
+
+    /// Returns the total number of terms of the aliquot sequences of all numbers
+    /// in the range. Terms shared by several sequences are counted for every
+    /// sequence, so this is the storage needed for all sequences, which helps
+    /// choosing the size of the cache.
+    pub fn term_count_estimate(&mut self, range: Range<T>) -> usize {
+        iter_range(range).map(|n| self.aliquot_seq(n).len()).sum()
+    }
+
+    /// Returns a histogram mapping the length of a cycle to the count of numbers
+    /// in the range, whose sequences are or run into a cycle of this length.
+    pub fn cycle_order_histogram(&mut self, range: Range<T>) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for n in iter_range(range) {
+            if let Some(len) = self.aliquot_seq(n).cycle_length() {
+                *histogram.entry(len).or_insert(0) += 1;
+            }
+        }
+        histogram
+    }
+
+    /// Returns a map from every perfect number to the count of aspiring numbers
+    /// in the range, whose sequences end with it.
+    pub fn aspiring_census(&mut self, range: Range<T>) -> BTreeMap<T, usize> {
+        let mut census = BTreeMap::new();
+        for n in iter_range(range) {
+            let aliquot_seq = self.aliquot_seq(n);
+            if let AliquotSeq::AspiringNumber(_) = aliquot_seq
+                && let Some(perfect) = aliquot_seq.terminal()
+            {
+                *census.entry(perfect).or_insert(0) += 1;
+            }
+        }
+        census
+    }
+
+    /// Returns the smallest number in the range, whose aliquot sequence consists
+    /// of exactly target_len numbers, or None, if there is no such number.
+    pub fn smallest_with_length(&mut self, target_len: usize, search_range: Range<T>) -> Option<T> {
+        iter_range(search_range).find(|&n| self.aliquot_seq(n).len() == target_len)
+    }
+
+    /// Returns an iterator over all numbers in the range, whose aliquot sequence
+    /// satisfies the predicate. The sequences are computed lazily.
+    fn filter_seq(
+        &mut self,
+        range: Range<T>,
+        pred: fn(&AliquotSeq<T>) -> bool,
+    ) -> impl Iterator<Item = T> {
+        iter_range(range).filter(move |&n| pred(&self.aliquot_seq(n)))
+    }
+
+    /// Returns an iterator over all perfect numbers in the range.
+    pub fn perfect_numbers(&mut self, range: Range<T>) -> impl Iterator<Item = T> {
+        self.filter_seq(range, |s| matches!(s, AliquotSeq::PerfectNumber(_)))
+    }
+
+    /// Returns an iterator over all prime numbers in the range.
+    pub fn primes(&mut self, range: Range<T>) -> impl Iterator<Item = T> {
+        self.filter_seq(range, |s| matches!(s, AliquotSeq::PrimeNumber(_)))
+    }
+
+    /// Returns an iterator over all amicable numbers in the range.
+    pub fn amicable_numbers(&mut self, range: Range<T>) -> impl Iterator<Item = T> {
+        self.filter_seq(range, |s| matches!(s, AliquotSeq::AmicableNumber(_)))
+    }
+
+    /// Returns an iterator over all sociable numbers in the range.
+    pub fn sociable_numbers(&mut self, range: Range<T>) -> impl Iterator<Item = T> {
+        self.filter_seq(range, |s| matches!(s, AliquotSeq::SociableNumber(_)))
+    }
+
+    /// Returns an iterator over all aspiring numbers in the range.
+    pub fn aspiring_numbers(&mut self, range: Range<T>) -> impl Iterator<Item = T> {
+        self.filter_seq(range, |s| matches!(s, AliquotSeq::AspiringNumber(_)))
+    }
+
+    /// Computes the aliquot sequence of a number n, optionally starting with the
+    /// already known aliquot sum of n. The next number in the sequence is computed
+    /// by the step function, if one is given. The computation stops, once the
+    /// stop flag is set.
+    fn aliquot_seq_from(
+        &mut self,
+        n: T,
+        first: Option<T>,
+        step: Option<&dyn StepFn<T>>,
+        stop: Option<&AtomicBool>,
+    ) -> AliquotSeq<T> {
+        let mut first = first;
+        // Store all values in a hash map for detecting cycles faster
+        let mut lut_seq = HashSet::<T>::new();
+        // The original number is the first number in the sequence
+        let mut seq = vec![n];
+        // Aliquot sequence is undefined for 0
+        if n == T::ZERO || n == T::ONE {
+            return AliquotSeq::Unknown(
+                seq,
+                UnknownMessage::new(UnknownReason::Undefined, "Undefined"),
+            );
+        }
+        // Check if the aliquot sequence has been computed for this number already
+        if let Some(aliquot_seq_cache) = self.cache.get(n) {
+            self.print_trace(format!("Found sequence for {n} in the cache"));
+            return aliquot_seq_cache;
+        }
+        for _i in 1..self.max_len_seq {
+            // The partial sequence is returned, but not added to the cache
+            if stop.is_some_and(|s| s.load(AtomicOrdering::Relaxed)) {
+                self.print_debug(format!("Sequence for {n} interrupted"));
+                return AliquotSeq::Unknown(
+                    seq,
+                    UnknownMessage::new(UnknownReason::Interrupted, "Interrupted"),
+                );
+            }
+            let len_seq = seq.len();
+            let last = seq[len_seq - 1];
+            let next = match first.take() {
+                Some(sum) => Ok(sum),
+                None => match step {
+                    Some(step) => step.step(last),
+                    None => {
+                        if self.track_factored {
+                            self.factored.insert(last);
+                        }
+                        self.next_term(last)
+                    }
+                },
+            };
+            match next {
+                Ok(next) => {
+                    // Abort, if a number in the sequence exceeds the maximum value allowed
+                    if next >= self.max_num {
+                        self.print_debug(format!("Numbers in the sequence for {n} exceed maximum"));
+                        let reason = format!("Maximum value {} exceeded", self.max_num);
+                        let aliquot_seq = AliquotSeq::Unknown(
+                            seq,
+                            UnknownMessage::new(UnknownReason::MaxValue, reason),
+                        );
+                        return self.cache.add_and_return(aliquot_seq);
+                    }
+                    // Stop at the terminal value before looking it up in the cache
+                    if next == self.terminal && next != T::ONE && next != n {
+                        self.print_debug(format!("Sequence for {n} converged to {next}"));
+                        seq.push(next);
+                        return self.cache.add_and_return(AliquotSeq::Convergent(seq));
+                    }
+                    // First check if the sum is stored in the cache, so we don't need
+                    // to compute the rest of the sequence
+                    if let Some(aliquot_seq_cache) = self.cache.get(next) {
+                        self.print_trace(format!("Found sequence for {next} in the cache to complete the sequence for {n}"));
+                        match aliquot_seq_cache {
+                            AliquotSeq::PerfectNumber(p) => {
+                                seq.push(p);
+                                return self.cache.add_and_return(AliquotSeq::AspiringNumber(seq));
+                            }
+                            AliquotSeq::PrimeNumber((p, one)) => {
+                                seq.push(p);
+                                seq.push(one);
+                                return self.cache.add_and_return(AliquotSeq::Convergent(seq));
+                            }
+                            AliquotSeq::Convergent(v) => {
+                                seq.extend_from_slice(v.as_slice());
+                                return self.cache.add_and_return(AliquotSeq::Convergent(seq));
+                            }
+                            AliquotSeq::AmicableNumber((a0, a1)) => {
+                                // Check if this is just the reverse order
+                                if a0 == next && a1 == n {
+                                    return AliquotSeq::AmicableNumber((n, next));
+                                } else {
+                                    // Otherwise n runs into cycle of amicable numbers
+                                    return self
+                                        .cache
+                                        .add_and_return(AliquotSeq::IntoCycle(seq, vec![a0, a1]));
+                                }
+                            }
+                            AliquotSeq::SociableNumber(v) => {
+                                // Runs into a cycle of sociable numbers
+                                return self
+                                    .cache
+                                    .add_and_return(AliquotSeq::IntoCycle(seq, v.clone()));
+                            }
+                            AliquotSeq::AspiringNumber(v) => {
+                                seq.extend_from_slice(v.as_slice());
+                                return self.cache.add_and_return(AliquotSeq::AspiringNumber(seq));
+                            }
+                            AliquotSeq::IntoCycle(v0, v1) => {
+                                seq.extend_from_slice(v0.as_slice());
+                                return self
+                                    .cache
+                                    .add_and_return(AliquotSeq::IntoCycle(seq, v1.clone()));
+                            }
+                            AliquotSeq::Unknown(v, message) => {
+                                // We ran into an unknown sequence
+                                seq.extend_from_slice(v.as_slice());
+                                let aliquot_seq = AliquotSeq::Unknown(seq, message);
+                                return self.cache.add_and_return(aliquot_seq);
+                            }
+                        }
+                    } else if next == T::ONE {
+                        self.print_debug(format!("Sequence for {n} converged to one"));
+                        match len_seq {
+                            1 => {
+                                // If only n is contained in the sequence so far, we have a prime
+                                return self
+                                    .cache
+                                    .add_and_return(AliquotSeq::PrimeNumber((n, T::ONE)));
+                            }
+                            _ => {
+                                // This is a normal sequence ending with a prime followed by one
+                                seq.push(T::ONE);
+                                return self.cache.add_and_return(AliquotSeq::Convergent(seq));
+                            }
+                        }
+                    } else if next == n {
+                        self.print_debug(format!("Sequence for {n} converged to {n}"));
+                        match len_seq {
+                            1 => {
+                                // There is only the original number in the sequence
+                                // so this must be a perfect number
+                                return self.cache.add_and_return(AliquotSeq::PerfectNumber(n));
+                            }
+                            2 => {
+                                // This is a repeating sequence with two numbers
+                                return self
+                                    .cache
+                                    .add_and_return(AliquotSeq::AmicableNumber((n, last)));
+                            }
+                            _ => {
+                                // This is a repeating sequence with more than two numbers
+                                return self.cache.add_and_return(AliquotSeq::SociableNumber(seq));
+                            }
+                        }
+                    } else if next == last {
+                        self.print_debug(format!(
+                            "Sequence for {n} converged into the perfect number {last}"
+                        ));
+                        // This sequence ended with a perfect number, so we have an aspiring number
+                        return self.cache.add_and_return(AliquotSeq::AspiringNumber(seq));
+                    } else if lut_seq.contains(&next) {
+                        self.print_debug(format!(
+                            "Sequence for {n} converged into a cycle of {next}"
+                        ));
+                        // Find the position in the sequence and split there
+                        // We now have a sequence, which converges into a cycle
+                        // since the original number n is not contained in the LUT
+                        let pos = seq
+                            .iter()
+                            .enumerate()
+                            .find(|&(_, &x)| x == next)
+                            .map(|(p, _)| p)
+                            .unwrap_or(0);
+                        let cycle = seq.split_off(pos);
+                        return self.cache.add_and_return(AliquotSeq::IntoCycle(seq, cycle));
+                    }
+                    seq.push(next);
+                    lut_seq.insert(next);
+                    // Forget the term leaving the window
+                    if let Some(window) = self.cycle_window
+                        && seq.len() > window + 1
+                    {
+                        lut_seq.remove(&seq[seq.len() - window - 1]);
+                    }
+                }
+                Err(err_msg) => {
+                    self.print_warning(format!(
+                        "Sequence of {n} unknown, because an error occurred: {err_msg}"
+                    ));
+                    let reason = format!("{err_msg}");
+                    let aliquot_seq = AliquotSeq::Unknown(
+                        seq,
+                        UnknownMessage::new(UnknownReason::Arithmetic, reason),
+                    );
+                    return self.cache.add_and_return(aliquot_seq);
+                }
+            }
+        }
+        let reason = format!("Maximum length {} of sequence exceeded", self.max_len_seq);
+        self.cache.add_and_return(AliquotSeq::Unknown(
+            seq,
+            UnknownMessage::new(UnknownReason::MaxLength, reason),
+        ))
+    }
+
+    /// Returns the associated cache object.
+    pub fn cache(&self) -> &Cache<T> {
+        &self.cache
+    }
+
+    /// Returns the associated cache object as mutable reference.
+    pub fn cache_mut(&mut self) -> &mut Cache<T> {
+        &mut self.cache
+    }
+
+    /// Returns the associated sieve object, if there is one.
+    pub fn sieve(&self) -> Option<&Sieve> {
+        self.sieve.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_gen<T: Number>(gener: &mut Generator<T>, n: T, exp: AliquotSeq<T>) {
+        assert_eq!(gener.aliquot_seq(n), exp);
+    }
+
+    #[test]
+    fn test_aliquot_seq_u16() {
+        let mut gener = Generator::<u16>::new();
+        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
+        test_gen(&mut gener, 3, AliquotSeq::PrimeNumber((3, 1)));
+        test_gen(&mut gener, 5, AliquotSeq::PrimeNumber((5, 1)));
+        test_gen(&mut gener, 6, AliquotSeq::PerfectNumber(6));
+        test_gen(&mut gener, 7, AliquotSeq::PrimeNumber((7, 1)));
+        test_gen(&mut gener, 11, AliquotSeq::PrimeNumber((11, 1)));
+        test_gen(
+            &mut gener,
+            12,
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]),
+        );
+        test_gen(&mut gener, 13, AliquotSeq::PrimeNumber((13, 1)));
+        test_gen(&mut gener, 17, AliquotSeq::PrimeNumber((17, 1)));
+        test_gen(&mut gener, 19, AliquotSeq::PrimeNumber((19, 1)));
+        test_gen(&mut gener, 28, AliquotSeq::PerfectNumber(28));
+        test_gen(&mut gener, 29, AliquotSeq::PrimeNumber((29, 1)));
+        test_gen(
+            &mut gener,
+            30,
+            AliquotSeq::Convergent(vec![
+                30, 42, 54, 66, 78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1,
+            ]),
+        );
+        test_gen(&mut gener, 31, AliquotSeq::PrimeNumber((31, 1)));
+        test_gen(&mut gener, 41, AliquotSeq::PrimeNumber((41, 1)));
+        test_gen(
+            &mut gener,
+            42,
+            AliquotSeq::Convergent(vec![42, 54, 66, 78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
+        );
+        test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
+        test_gen(
+            &mut gener,
+            54,
+            AliquotSeq::Convergent(vec![54, 66, 78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
+        );
+        test_gen(
+            &mut gener,
+            60,
+            AliquotSeq::Convergent(vec![60, 108, 172, 136, 134, 70, 74, 40, 50, 43, 1]),
+        );
+        test_gen(
+            &mut gener,
+            78,
+            AliquotSeq::Convergent(vec![78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
+        );
+        test_gen(
+            &mut gener,
+            90,
+            AliquotSeq::Convergent(vec![90, 144, 259, 45, 33, 15, 9, 4, 3, 1]),
+        );
+        test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
+        test_gen(
+            &mut gener,
+            96,
+            AliquotSeq::Convergent(vec![96, 156, 236, 184, 176, 196, 203, 37, 1]),
+        );
+        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
+        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
+    }
+
+    #[test]
+    fn test_aliquot_seq_u32() {
+        let mut gener = Generator::<u32>::new();
+        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
+        test_gen(&mut gener, 3, AliquotSeq::PrimeNumber((3, 1)));
+        test_gen(&mut gener, 6, AliquotSeq::PerfectNumber(6));
+        test_gen(&mut gener, 17, AliquotSeq::PrimeNumber((17, 1)));
+        test_gen(&mut gener, 19, AliquotSeq::PrimeNumber((19, 1)));
+        test_gen(&mut gener, 41, AliquotSeq::PrimeNumber((41, 1)));
+        test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
+        test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
+        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
+        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
+        test_gen(
+            &mut gener,
+            1264460,
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+        );
+        test_gen(
+            &mut gener,
+            276,
+            AliquotSeq::Unknown(
+                vec![
+                    276, 396, 696, 1104, 1872, 3770, 3790, 3050, 2716, 2772, 5964, 10164, 19628,
+                    19684, 22876, 26404, 30044, 33796, 38780, 54628, 54684, 111300, 263676, 465668,
+                    465724, 465780, 1026060, 2325540, 5335260, 11738916, 23117724, 45956820,
+                    121129260, 266485716, 558454764, 1092873236, 1470806764, 1471882804,
+                    1642613196, 2737688884, 2740114636, 2791337780,
+                ],
+                UnknownMessage::new(
+                    UnknownReason::Arithmetic,
+                    "Overflow error: 4213448791 plus 99690663 exceeds maximum 4294967295",
+                ),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_aliquot_seq_u64() {
+        let mut gener = Generator::<u64>::new();
+        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
+        test_gen(&mut gener, 3, AliquotSeq::PrimeNumber((3, 1)));
+        test_gen(&mut gener, 6, AliquotSeq::PerfectNumber(6));
+        test_gen(&mut gener, 17, AliquotSeq::PrimeNumber((17, 1)));
+        test_gen(&mut gener, 19, AliquotSeq::PrimeNumber((19, 1)));
+        test_gen(&mut gener, 41, AliquotSeq::PrimeNumber((41, 1)));
+        test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
+        test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
+        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
+        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
+        test_gen(
+            &mut gener,
+            1264460,
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+        );
+        test_gen(
+            &mut gener,
+            138,
+            AliquotSeq::Convergent(vec![
+                138,
+                150,
+                222,
+                234,
+                312,
+                528,
+                960,
+                2088,
+                3762,
+                5598,
+                6570,
+                10746,
+                13254,
+                13830,
+                19434,
+                20886,
+                21606,
+                25098,
+                26742,
+                26754,
+                40446,
+                63234,
+                77406,
+                110754,
+                171486,
+                253458,
+                295740,
+                647748,
+                1077612,
+                1467588,
+                1956812,
+                2109796,
+                1889486,
+                953914,
+                668966,
+                353578,
+                176792,
+                254128,
+                308832,
+                502104,
+                753216,
+                1240176,
+                2422288,
+                2697920,
+                3727264,
+                3655076,
+                2760844,
+                2100740,
+                2310856,
+                2455544,
+                3212776,
+                3751064,
+                3282196,
+                2723020,
+                3035684,
+                2299240,
+                2988440,
+                5297320,
+                8325080,
+                11222920,
+                15359480,
+                19199440,
+                28875608,
+                25266172,
+                19406148,
+                26552604,
+                40541052,
+                54202884,
+                72270540,
+                147793668,
+                228408732,
+                348957876,
+                508132204,
+                404465636,
+                303708376,
+                290504024,
+                312058216,
+                294959384,
+                290622016,
+                286081174,
+                151737434,
+                75868720,
+                108199856,
+                101437396,
+                76247552,
+                76099654,
+                42387146,
+                21679318,
+                12752594,
+                7278382,
+                3660794,
+                1855066,
+                927536,
+                932464,
+                1013592,
+                1546008,
+                2425752,
+                5084088,
+                8436192,
+                13709064,
+                20563656,
+                33082104,
+                57142536,
+                99483384,
+                245978376,
+                487384824,
+                745600776,
+                1118401224,
+                1677601896,
+                2538372504,
+                4119772776,
+                8030724504,
+                14097017496,
+                21148436904,
+                40381357656,
+                60572036544,
+                100039354704,
+                179931895322,
+                94685963278,
+                51399021218,
+                28358080762,
+                18046051430,
+                17396081338,
+                8698040672,
+                8426226964,
+                6319670230,
+                5422685354,
+                3217383766,
+                1739126474,
+                996366646,
+                636221402,
+                318217798,
+                195756362,
+                101900794,
+                54202694,
+                49799866,
+                24930374,
+                17971642,
+                11130830,
+                8904682,
+                4913018,
+                3126502,
+                1574810,
+                1473382,
+                736694,
+                541162,
+                312470,
+                249994,
+                127286,
+                69898,
+                34952,
+                34708,
+                26038,
+                13994,
+                7000,
+                11720,
+                14740,
+                19532,
+                16588,
+                18692,
+                14026,
+                7016,
+                6154,
+                3674,
+                2374,
+                1190,
+                1402,
+                704,
+                820,
+                944,
+                916,
+                694,
+                350,
+                394,
+                200,
+                265,
+                59,
+                1,
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_aliquot_seq_u128() {
+        let mut gener = Generator::<u128>::new();
+        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
         test_gen(&mut gener, 3, AliquotSeq::PrimeNumber((3, 1)));
         test_gen(&mut gener, 6, AliquotSeq::PerfectNumber(6));
         test_gen(&mut gener, 17, AliquotSeq::PrimeNumber((17, 1)));
@@ -603,250 +3114,1731 @@ mod tests {
         test_gen(&mut gener, 41, AliquotSeq::PrimeNumber((41, 1)));
         test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
         test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
-        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
-        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
+        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
+        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
+        test_gen(
+            &mut gener,
+            1264460,
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+        );
+    }
+
+    #[test]
+    fn test_first_increase_index() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(gener.aliquot_seq(7).first_increase_index(), None);
+        assert_eq!(gener.aliquot_seq(6).first_increase_index(), None);
+        assert_eq!(gener.aliquot_seq(95).first_increase_index(), None);
+        assert_eq!(gener.aliquot_seq(138).first_increase_index(), Some(1));
+        assert_eq!(gener.aliquot_seq(284).first_increase_index(), None);
+        assert_eq!(gener.aliquot_seq(220).first_increase_index(), Some(1));
+        assert_eq!(gener.aliquot_seq(40).first_increase_index(), Some(1));
+        assert_eq!(gener.aliquot_seq(70).first_increase_index(), Some(1));
+        assert_eq!(gener.aliquot_seq(74).first_increase_index(), None);
+        assert_eq!(gener.aliquot_seq(44).first_increase_index(), Some(2));
+    }
+
+    #[test]
+    fn test_compress() {
+        let mut gener = Generator::<u64>::with_params(1_000_000_000_000, 1000, 0, false);
+        let aliquot_seq = gener.aliquot_seq(276);
+        assert!(aliquot_seq.len() > 40);
+        let bytes = aliquot_seq.compress();
+        assert_eq!(
+            AliquotSeq::<u64>::decompress(&bytes),
+            Ok(aliquot_seq.clone())
+        );
+        assert!(bytes.len() < aliquot_seq.to_bytes().len());
+        assert!(bytes.len() < aliquot_seq.memory_footprint());
+        let aliquot_seqs = vec![
+            AliquotSeq::PerfectNumber(u128::MAX),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]),
+            AliquotSeq::AmicableNumber((220, 284)),
+            AliquotSeq::IntoCycle(vec![562, 284], vec![220, 284]),
+            AliquotSeq::Unknown(
+                vec![u128::MAX, 0, u128::MAX],
+                UnknownMessage::new(UnknownReason::Undefined, "Undefined"),
+            ),
+        ];
+        for aliquot_seq in aliquot_seqs {
+            let bytes = aliquot_seq.compress();
+            assert_eq!(AliquotSeq::<u128>::decompress(&bytes), Ok(aliquot_seq));
+            assert!(AliquotSeq::<u128>::decompress(&bytes[..bytes.len() - 1]).is_err());
+        }
+        // Numbers exceeding the maximum of the type are rejected
+        let bytes = AliquotSeq::<u32>::PerfectNumber(70000).compress();
+        assert!(AliquotSeq::<u16>::decompress(&bytes).is_err());
+        assert_eq!(
+            AliquotSeq::<u16>::PerfectNumber(28).memory_footprint(),
+            std::mem::size_of::<AliquotSeq<u16>>()
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        let aliquot_seqs = vec![
+            AliquotSeq::PerfectNumber(6),
+            AliquotSeq::PrimeNumber((7, 1)),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]),
+            AliquotSeq::AmicableNumber((220, 284)),
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+            AliquotSeq::AspiringNumber(vec![95, 25, 6]),
+            AliquotSeq::IntoCycle(vec![562, 284], vec![220, 284]),
+            AliquotSeq::Unknown(
+                vec![276, 396, 696],
+                UnknownMessage::new(UnknownReason::MaxValue, "Maximum value 700 exceeded"),
+            ),
+        ];
+        for aliquot_seq in aliquot_seqs {
+            let bytes = aliquot_seq.to_bytes();
+            assert_eq!(
+                AliquotSeq::<u32>::from_bytes(&bytes),
+                Ok(aliquot_seq.clone())
+            );
+            // Truncated or extended data is rejected
+            assert!(AliquotSeq::<u32>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+            let mut extended = bytes.clone();
+            extended.push(0);
+            assert!(AliquotSeq::<u32>::from_bytes(&extended).is_err());
+        }
+        let bytes = AliquotSeq::<u16>::PerfectNumber(28).to_bytes();
+        assert_eq!(bytes, vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 28, 0]);
+        assert_eq!(
+            AliquotSeq::<u16>::from_bytes(&bytes),
+            Ok(AliquotSeq::PerfectNumber(28))
+        );
+        assert!(AliquotSeq::<u16>::from_bytes(&[8, 0, 0, 0, 0, 0, 0, 0, 0]).is_err());
+        assert!(AliquotSeq::<u16>::from_bytes(&[]).is_err());
+        // The number of terms must match the variant
+        let encode = |tag: u8, terms: &[u16]| -> Vec<u8> {
+            let mut bytes = vec![tag];
+            bytes.extend_from_slice(&(terms.len() as u64).to_le_bytes());
+            for t in terms {
+                bytes.extend_from_slice(&t.to_le_bytes());
+            }
+            bytes
+        };
+        for (tag, terms) in [
+            (0, &[6, 6][..]),
+            (1, &[7][..]),
+            (1, &[7, 1, 1][..]),
+            (2, &[12][..]),
+            (3, &[220, 284, 220][..]),
+            (4, &[1000, 2000][..]),
+            (5, &[95][..]),
+        ] {
+            assert!(
+                AliquotSeq::<u16>::from_bytes(&encode(tag, terms)).is_err(),
+                "{tag} {terms:?}"
+            );
+        }
+        let mut bytes = encode(6, &[562]);
+        bytes.extend_from_slice(&encode(6, &[284])[1..]);
+        assert!(AliquotSeq::<u16>::from_bytes(&bytes).is_err());
+        assert_eq!(
+            AliquotSeq::<u16>::from_bytes(&encode(1, &[7, 1])),
+            Ok(AliquotSeq::PrimeNumber((7, 1)))
+        );
+        // The reason of unknown sequences must be valid
+        let mut bytes = encode(7, &[276]);
+        bytes.push(5);
+        bytes.extend_from_slice(&0u64.to_le_bytes());
+        assert!(AliquotSeq::<u16>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_divisor_aliquot_sums() {
+        let expected = vec![(1, 0), (2, 1), (3, 1), (4, 3), (6, 6), (12, 16)];
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.divisor_aliquot_sums(12), Ok(expected.clone()));
+        // The sums of cached numbers are taken from their sequences
+        gener.aliquot_seq(12);
+        gener.aliquot_seq(6);
+        assert_eq!(gener.divisor_aliquot_sums(12), Ok(expected));
+        assert_eq!(gener.divisor_aliquot_sums(1), Ok(vec![(1, 0)]));
+        assert_eq!(
+            gener.divisor_aliquot_sums(220).unwrap().last(),
+            Some(&(220, 284))
+        );
+        assert!(gener.divisor_aliquot_sums(0).is_err());
+    }
+
+    #[test]
+    fn test_perfect_numbers_up_to() {
+        assert_eq!(
+            Generator::<u32>::perfect_numbers_up_to(10000),
+            vec![6, 28, 496, 8128]
+        );
+        assert_eq!(
+            Generator::<u16>::perfect_numbers_up_to(5),
+            Vec::<u16>::new()
+        );
+        assert_eq!(Generator::<u16>::perfect_numbers_up_to(u16::MAX).len(), 4);
+        assert_eq!(
+            Generator::<u64>::perfect_numbers_up_to(u64::MAX).last(),
+            Some(&2305843008139952128)
+        );
+        let perfect = Generator::<u128>::perfect_numbers_up_to(u128::MAX);
+        assert_eq!(perfect.len(), 9);
+        assert_eq!(perfect[8], (1 << 60) * ((1 << 61) - 1));
+        // All numbers are recognized as perfect by the generator
+        let mut gener = Generator::<u32>::new();
+        for n in Generator::<u32>::perfect_numbers_up_to(u32::MAX) {
+            assert_eq!(gener.aliquot_seq(n), AliquotSeq::PerfectNumber(n));
+        }
+    }
+
+    #[test]
+    fn test_abundancy() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.abundancy(6), Ok(2.0));
+        assert_eq!(gener.abundancy(28), Ok(2.0));
+        assert!((gener.abundancy(12).unwrap() - 7.0 / 3.0).abs() < 1e-12);
+        for p in [2u32, 7, 97, 65537] {
+            assert_eq!(gener.abundancy(p), Ok((p + 1) as f64 / p as f64));
+        }
+        assert_eq!(gener.abundancy(1), Ok(1.0));
+        assert_eq!(gener.abundancy(120), Ok(3.0));
+        assert!(gener.abundancy(0).is_err());
+        assert!(Generator::<u16>::new().abundancy(65520).is_err());
+    }
+
+    #[test]
+    fn test_multiperfect_index() {
+        assert_eq!(Generator::<u32>::sigma(120), Ok(360));
+        assert_eq!(Generator::<u32>::sigma(1), Ok(1));
+        assert!(Generator::<u16>::sigma(65520).is_err());
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.multiperfect_index(120), Ok(Some(3)));
+        assert_eq!(gener.multiperfect_index(6), Ok(Some(2)));
+        assert_eq!(gener.multiperfect_index(28), Ok(Some(2)));
+        assert_eq!(gener.multiperfect_index(30240), Ok(Some(4)));
+        assert_eq!(gener.multiperfect_index(12), Ok(None));
+        assert_eq!(gener.multiperfect_index(7), Ok(None));
+        assert_eq!(gener.multiperfect_index(1), Ok(None));
+        assert_eq!(gener.multiperfect_index(0), Ok(None));
+    }
+
+    #[test]
+    fn test_cache_get_prime() {
+        let mut cache = Cache::<u32>::new(1000);
+        cache.add(AliquotSeq::Convergent(vec![40, 50, 43, 1]));
+        assert_eq!(cache.get(43), Some(AliquotSeq::PrimeNumber((43, 1))));
+        assert_eq!(cache.get(50), Some(AliquotSeq::Convergent(vec![50, 43, 1])));
+        assert_eq!(cache.get(1), None);
+    }
+
+    #[test]
+    fn test_cache_prime() {
+        let mut gener = Generator::<u32>::new();
+        test_gen(&mut gener, 40, AliquotSeq::Convergent(vec![40, 50, 43, 1]));
+        test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
+        test_gen(&mut gener, 50, AliquotSeq::Convergent(vec![50, 43, 1]));
+    }
+
+    #[test]
+    fn test_fork() {
+        let mut gener = Generator::<u32>::with_params(1000, 100, 500, false);
+        gener.set_sieve(Some(Sieve::new(100)));
+        gener.aliquot_seq(12);
+        let mut forked = gener.fork();
+        assert_eq!(forked.cache().n_seq(), 0);
+        assert!(forked.sieve().is_some());
+        for n in [12, 30, 95, 220, 276] {
+            assert_eq!(forked.aliquot_seq(n), gener.aliquot_seq(n));
+        }
+        forked.aliquot_seq(138);
+        assert!(forked.cache().get(138).is_some());
+        assert!(gener.cache().get(138).is_none());
+    }
+
+    #[test]
+    fn test_preload() {
+        let mut gener = Generator::<u64>::new();
+        let aliquot_seq = gener.aliquot_seq(138);
+        let terms = aliquot_seq.seq();
+        let mut gener = Generator::<u64>::new();
+        gener.preload([aliquot_seq.clone(), AliquotSeq::AmicableNumber((220, 284))]);
+        assert_eq!(gener.cache().n_seq(), 3);
+        // Numbers inside the sequence of 138 are reconstructed from the cache
+        assert_eq!(
+            gener.cache().get(terms[10]),
+            Some(AliquotSeq::Convergent(terms[10..].to_vec()))
+        );
+        assert_eq!(gener.aliquot_seq(terms[10]).seq(), terms[10..].to_vec());
+        assert_eq!(gener.aliquot_seq(138), aliquot_seq);
+        assert_eq!(
+            gener.cache().get(284),
+            Some(AliquotSeq::AmicableNumber((284, 220)))
+        );
+    }
+
+    #[test]
+    fn test_set_terminal_value() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(
+            gener.aliquot_seq(12),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1])
+        );
+        gener.set_terminal_value(16);
+        assert_eq!(gener.aliquot_seq(12), AliquotSeq::Convergent(vec![12, 16]));
+        assert_eq!(
+            gener.aliquot_seq(16),
+            AliquotSeq::Convergent(vec![16, 15, 9, 4, 3, 1])
+        );
+        assert_eq!(gener.aliquot_seq(7), AliquotSeq::PrimeNumber((7, 1)));
+        // The tail before the terminal value is taken from the cache
+        gener.set_terminal_value(16);
+        assert_eq!(
+            gener.aliquot_seq(121),
+            AliquotSeq::Convergent(vec![121, 12, 16])
+        );
+        assert_eq!(gener.aliquot_seq(12), AliquotSeq::Convergent(vec![12, 16]));
+        gener.set_terminal_value(6);
+        assert_eq!(gener.aliquot_seq(25), AliquotSeq::Convergent(vec![25, 6]));
+        assert_eq!(gener.aliquot_seq(6), AliquotSeq::PerfectNumber(6));
+        gener.set_terminal_value(1);
+        assert_eq!(
+            gener.aliquot_seq(25),
+            AliquotSeq::AspiringNumber(vec![25, 6])
+        );
+        assert_eq!(
+            gener.aliquot_seq(12),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1])
+        );
+    }
+
+    #[test]
+    fn test_same_trajectory() {
+        let mut gener = Generator::<u32>::new();
+        assert!(gener.same_trajectory(12, 16));
+        assert!(gener.same_trajectory(16, 12));
+        assert!(gener.same_trajectory(12, 12));
+        // Both merge at 8
+        assert!(gener.same_trajectory(49, 10));
+        assert!(gener.same_trajectory(562, 284));
+        assert!(!gener.same_trajectory(12, 220));
+        assert!(!gener.same_trajectory(12, 10));
+        assert!(!gener.same_trajectory(1, 7));
+    }
+
+    #[test]
+    fn test_coverage() {
+        let mut gener = Generator::<u32>::new();
+        // 10, 8, 7, 1 and 11, 1 and 12, 16, 15, 9, 4, 3, 1
+        assert_eq!(gener.coverage(10..13), 11);
+        assert_eq!(gener.coverage(6..7), 1);
+        assert_eq!(gener.coverage(220..221), 2);
+        assert_eq!(gener.coverage(5..5), 0);
+    }
+
+    #[test]
+    fn test_cycle_window() {
+        // Counts down to the bottom of the cycle and jumps back to its top
+        let countdown = |top: u32, bottom: u32| {
+            move |n: u32| -> Result<u32, AliquotError> { Ok(if n > bottom { n - 1 } else { top }) }
+        };
+        let mut gener = Generator::<u32>::with_params(u32::MAX, 1000, 0, false);
+        gener.set_cycle_window(Some(5));
+        assert_eq!(
+            gener.aliquot_seq_with(15, &countdown(12, 10)),
+            AliquotSeq::IntoCycle(vec![15, 14, 13], vec![12, 11, 10])
+        );
+        // The cycle of ten numbers doesn't fit into the window
+        let aliquot_seq = gener.aliquot_seq_with(30, &countdown(19, 10));
+        assert_eq!(aliquot_seq.unknown_reason(), Some(UnknownReason::MaxLength));
+        gener.set_cycle_window(None);
+        let aliquot_seq = gener.aliquot_seq_with(30, &countdown(19, 10));
+        assert_eq!(aliquot_seq.cycle_length(), Some(10));
+        // Cycles through the first number are detected regardless of the window
+        gener.set_cycle_window(Some(1));
+        assert_eq!(
+            gener.aliquot_seq(1264460),
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184])
+        );
+        // 562 runs into the amicable pair 284, 220, which needs a window of two
+        assert_eq!(
+            gener.aliquot_seq(562).unknown_reason(),
+            Some(UnknownReason::MaxLength)
+        );
+        gener.set_cycle_window(Some(2));
+        assert_eq!(gener.aliquot_seq(562).cycle_length(), Some(2));
+    }
+
+    #[test]
+    fn test_aspiring_census() {
+        let mut gener = Generator::<u64>::with_params(1_000_000_000, 1000, 100_000, false);
+        assert_eq!(gener.aspiring_census(1..100), BTreeMap::from([(6, 2)]));
+        assert_eq!(gener.aspiring_census(25..26), BTreeMap::from([(6, 1)]));
+        let census = gener.aspiring_census(1..2000);
+        assert!(census[&6] > 2);
+        assert!(census.keys().all(|&p| gener.is_perfect(p).unwrap()));
+        // Perfect numbers themselves are not aspiring
+        assert!(gener.aspiring_census(6..7).is_empty());
+    }
+
+    #[test]
+    fn test_top_k_longest() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(
+            gener.top_k_longest(1..100, 3),
+            vec![(30, 15), (42, 14), (54, 13)]
+        );
+        // The longest sequences of all numbers are found regardless of k
+        let mut all = (100..200)
+            .map(|n| (n, gener.aliquot_seq(n).len()))
+            .collect::<Vec<(u64, usize)>>();
+        all.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        assert_eq!(gener.top_k_longest(100..200, 5), all[..5].to_vec());
+        assert_eq!(gener.top_k_longest(100..200, 1000), all);
+        // Both 9 and 10 have sequences of length four as well as 60 and 78 of 11
+        assert_eq!(gener.top_k_longest(9..11, 1), vec![(9, 4)]);
+        assert_eq!(gener.top_k_longest(1..100, 5)[4], (60, 11));
+        assert!(gener.top_k_longest(1..100, 0).is_empty());
+        assert!(gener.top_k_longest(5..5, 3).is_empty());
+    }
+
+    #[test]
+    fn test_longest_in_range() {
+        let mut gener = Generator::<u64>::new();
+        let (n, aliquot_seq) = gener.longest_in_range(1..100).unwrap();
+        assert_eq!(n, 30);
+        assert_eq!(aliquot_seq.len(), 15);
+        // Both 9 and 10 have sequences of length four
+        let (n, aliquot_seq) = gener.longest_in_range(9..12).unwrap();
+        assert_eq!(n, 9);
+        assert_eq!(aliquot_seq, AliquotSeq::Convergent(vec![9, 4, 3, 1]));
+        let (n, aliquot_seq) = gener.longest_in_range(100..200).unwrap();
+        assert_eq!(n, 138);
+        assert_eq!(aliquot_seq.len(), 178);
+        assert_eq!(gener.longest_in_range(5..5), None);
+    }
+
+    #[test]
+    fn test_trace_back() {
+        let mut gener = Generator::<u32>::new();
+        let chain = gener.trace_back(6, 10_000, 3);
+        assert_eq!(chain[..3], [6, 25, 95]);
+        assert_eq!(chain.len(), 4);
+        for w in chain.windows(2) {
+            assert_eq!(Generator::<u32>::aliquot_sum(w[1]), Ok(w[0]));
+        }
+        // Untouchable numbers have no preimages
+        assert_eq!(gener.trace_back(5, 10_000, 3), vec![5]);
+        assert_eq!(gener.trace_back(6, 10_000, 0), vec![6]);
+    }
+
+    #[test]
+    fn test_smallest_with_length() {
+        let mut gener = Generator::<u32>::new();
+        // The sequence of one is undefined and consists of one only
+        assert_eq!(gener.smallest_with_length(1, 1..100), Some(1));
+        assert_eq!(gener.smallest_with_length(1, 2..100), Some(6));
+        assert_eq!(gener.smallest_with_length(2, 1..100), Some(2));
+        assert_eq!(gener.smallest_with_length(4, 1..100), Some(9));
+        assert_eq!(gener.smallest_with_length(15, 1..100), Some(30));
+        assert_eq!(gener.smallest_with_length(15, 31..100), None);
+        assert_eq!(gener.smallest_with_length(1, 7..100), Some(28));
+    }
+
+    #[test]
+    fn test_aliquot_seq_with() {
+        let mut gener = Generator::<u32>::new();
+        for n in [12, 30, 95, 220, 1264460] {
+            assert_eq!(gener.aliquot_seq_with(n, &AliquotSum), gener.aliquot_seq(n));
+        }
+        let halve = |n: u32| -> Result<u32, AliquotError> { Ok(n / 2) };
+        assert_eq!(
+            gener.aliquot_seq_with(12, &halve),
+            AliquotSeq::Convergent(vec![12, 6, 3, 1])
+        );
+        let swap = |n: u32| -> Result<u32, AliquotError> { Ok(if n == 5 { 7 } else { 5 }) };
+        assert_eq!(
+            gener.aliquot_seq_with(5, &swap),
+            AliquotSeq::AmicableNumber((5, 7))
+        );
+        // The cache still contains the aliquot sequences only
+        assert_eq!(
+            gener.cache().get(12),
+            Some(AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]))
+        );
+        assert_eq!(gener.cache().get(5), None);
+    }
+
+    #[test]
+    fn test_canonical() {
+        let mut gener = Generator::<u32>::new();
+        let sociable0 = gener.aliquot_seq(1264460);
+        let sociable1 = gener.aliquot_seq(1727636);
+        assert_ne!(sociable0, sociable1);
+        assert_eq!(sociable0.canonical(), sociable1.canonical());
+        assert_eq!(
+            sociable1.canonical(),
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184])
+        );
+        assert_eq!(
+            gener.aliquot_seq(284).canonical(),
+            AliquotSeq::AmicableNumber((220, 284))
+        );
+        assert_eq!(
+            gener.aliquot_seq(220).canonical(),
+            AliquotSeq::AmicableNumber((220, 284))
+        );
+        assert_eq!(gener.aliquot_seq(12).canonical(), gener.aliquot_seq(12));
+    }
+
+    #[test]
+    fn test_truncate_above() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(
+            gener.aliquot_seq(138).truncate_above(1000),
+            vec![138, 150, 222, 234, 312, 528, 960, 2088]
+        );
+        assert_eq!(
+            gener.aliquot_seq(12).truncate_above(1000),
+            vec![12, 16, 15, 9, 4, 3, 1]
+        );
+        assert_eq!(gener.aliquot_seq(12).truncate_above(13), vec![12, 16]);
+        assert_eq!(
+            gener.aliquot_seq(1264460).truncate_above(1500000),
+            vec![1264460, 1547860]
+        );
+    }
+
+    #[test]
+    fn test_term_sum() {
+        let mut gener = Generator::<u16>::new();
+        assert_eq!(gener.aliquot_seq(12).term_sum(), Some(60));
+        assert_eq!(gener.aliquot_seq(220).term_sum(), Some(504));
+        assert_eq!(gener.aliquot_seq(6).term_sum(), Some(6));
+        let aliquot_seq = AliquotSeq::<u16>::Convergent(vec![60000, 5000, 500, 1]);
+        assert_eq!(aliquot_seq.term_sum(), Some(65501));
+        let aliquot_seq = AliquotSeq::<u16>::Convergent(vec![60000, 6000, 1]);
+        assert_eq!(aliquot_seq.term_sum(), None);
+    }
+
+    #[test]
+    fn test_record_highs() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(
+            gener.aliquot_seq(30).seq(),
+            vec![30, 42, 54, 66, 78, 90, 144, 259, 45, 33, 15, 9, 4, 3, 1]
+        );
+        assert_eq!(
+            gener.aliquot_seq(30).record_highs(),
+            vec![
+                (0, 30),
+                (1, 42),
+                (2, 54),
+                (3, 66),
+                (4, 78),
+                (5, 90),
+                (6, 144),
+                (7, 259)
+            ]
+        );
+        assert_eq!(gener.aliquot_seq(12).record_highs(), vec![(0, 12), (1, 16)]);
+        assert_eq!(gener.aliquot_seq(28).record_highs(), vec![(0, 28)]);
+    }
+
+    #[test]
+    fn test_differences() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(
+            gener.aliquot_seq(12).differences(),
+            vec![4, -1, -6, -5, -1, -2]
+        );
+        assert_eq!(gener.aliquot_seq(220).differences(), vec![64]);
+        assert_eq!(gener.aliquot_seq(6).differences(), vec![]);
+        let aliquot_seq = AliquotSeq::<u128>::Convergent(vec![u128::MAX, 1]);
+        assert_eq!(aliquot_seq.differences(), vec![i128::MIN]);
+    }
+
+    #[test]
+    fn test_growth_ratio() {
+        let mut gener = Generator::<u64>::new();
+        assert!(gener.aliquot_seq(97).growth_ratio() < 1.0);
+        assert!(gener.aliquot_seq(12).growth_ratio() < 1.0);
+        assert_eq!(gener.aliquot_seq(28).growth_ratio(), 1.0);
+        let ratio = gener.aliquot_seq(220).growth_ratio();
+        assert!((ratio - 284.0 / 220.0).abs() < 1e-12);
+        // The first terms of the open sequence of 276
+        let mut gener = Generator::<u64>::with_params(u64::MAX, 20, 0, false);
+        assert!(gener.aliquot_seq(276).growth_ratio() > 1.0);
+    }
+
+    #[test]
+    fn test_fate() {
+        let mut gener = Generator::<u64>::new();
+        let fate = |gener: &mut Generator<u64>, n| {
+            let aliquot_seq = gener.aliquot_seq(n);
+            (aliquot_seq.fate(), aliquot_seq.terminal())
+        };
+        assert_eq!(fate(&mut gener, 1), (Fate::Open, None));
+        assert_eq!(fate(&mut gener, 7), (Fate::Terminates, Some(1)));
+        assert_eq!(fate(&mut gener, 12), (Fate::Terminates, Some(1)));
+        assert_eq!(fate(&mut gener, 28), (Fate::Perfect, Some(28)));
+        assert_eq!(fate(&mut gener, 95), (Fate::Perfect, Some(6)));
+        assert_eq!(fate(&mut gener, 220), (Fate::Cycle, None));
+        assert_eq!(fate(&mut gener, 12496), (Fate::Cycle, None));
+        assert_eq!(fate(&mut gener, 562), (Fate::Cycle, None));
+    }
+
+    #[test]
+    fn test_aliquot_sum_stream() {
+        let mut gener = Generator::<u32>::new();
+        for (n, sum) in gener.aliquot_sum_stream(2..100000) {
+            assert_eq!(sum, Generator::<u32>::aliquot_sum(n), "{n}");
+        }
+        let sums = gener.aliquot_sum_stream(0..13).collect::<Vec<_>>();
+        assert_eq!(sums[0], (0, Ok(0)));
+        assert_eq!(sums[1], (1, Ok(0)));
+        assert_eq!(sums[12], (12, Ok(16)));
+        let sums = gener.aliquot_sum_stream(1264460..1264470);
+        assert_eq!(
+            sums.take(1).collect::<Vec<_>>(),
+            vec![(1264460, Ok(1547860))]
+        );
+        // The sum of 65520 exceeds the maximum of u16
+        let mut gener = Generator::<u16>::new();
+        let sums = gener.aliquot_sum_stream(65519..65521).collect::<Vec<_>>();
+        assert_eq!(sums[0], (65519, Generator::<u16>::aliquot_sum(65519)));
+        assert!(sums[1].1.is_err());
+        assert_eq!(gener.aliquot_sum_stream(5..5).count(), 0);
+        // Large numbers are sieved in segments without allocating a sieve up to them
+        let mut gener = Generator::<u64>::new();
+        let n = 1_000_000_000_000;
+        for (m, sum) in gener.aliquot_sum_stream(n..n + 3) {
+            assert_eq!(sum, Generator::<u64>::aliquot_sum(m), "{m}");
+        }
+        let sums = gener.aliquot_sum_stream(1_048_000..1_049_000);
+        for (m, sum) in sums {
+            assert_eq!(sum, Generator::<u64>::aliquot_sum(m), "{m}");
+        }
+    }
+
+    #[test]
+    fn test_by_abundance() {
+        let mut gener = Generator::<u32>::new();
+        let abundances = gener.by_abundance(1..50);
+        assert_eq!(abundances[0], (48, 28));
+        assert_eq!(abundances[1], (36, 19));
+        assert_eq!(&abundances[2..6], &[(24, 12), (30, 12), (42, 12), (40, 10)]);
+        assert_eq!(&abundances[9..], &[(6, 0), (28, 0)]);
+        assert!(abundances.windows(2).all(|w| w[0].1 >= w[1].1));
+        for (n, abundance) in abundances {
+            assert_eq!(Generator::<u32>::aliquot_sum(n), Ok(n + abundance));
+        }
+        assert!(gener.by_abundance(1..6).is_empty());
+        // The sums of abundant numbers like 65520 exceed the maximum of u16
+        assert!(Generator::<u16>::aliquot_sum(65520).is_err());
+        let abundances = Generator::<u16>::new().by_abundance(65000..65535);
+        assert!(abundances.iter().all(|&(n, _)| n != 65520));
+        let expected = (65000..65535)
+            .filter(|&n| Generator::<u16>::aliquot_sum(n).is_ok_and(|sum| sum >= n))
+            .count();
+        assert_eq!(abundances.len(), expected);
+    }
+
+    #[test]
+    fn test_balanced_chunks() {
+        let chunks = Generator::<u64>::balanced_chunks(1..1_000_001, 4);
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[0].start, 1);
+        assert_eq!(chunks[3].end, 1_000_001);
+        assert!(chunks.windows(2).all(|w| w[0].end == w[1].start));
+        // Chunks of larger numbers are smaller
+        let sizes = chunks.iter().map(|c| c.end - c.start).collect::<Vec<u64>>();
+        assert!(sizes.windows(2).all(|w| w[0] > w[1]), "{sizes:?}");
+        // The costs of all chunks are roughly equal
+        let cost = |c: &Range<u64>| c.clone().map(|n| (n as f64).sqrt()).sum::<f64>();
+        let costs = chunks.iter().map(cost).collect::<Vec<f64>>();
+        assert!(
+            costs.iter().all(|c| (c / costs[0] - 1.0).abs() < 0.01),
+            "{costs:?}"
+        );
+        // Tiny ranges result in less chunks
+        assert_eq!(
+            Generator::<u16>::balanced_chunks(10..12, 5),
+            vec![10..11, 11..12]
+        );
+        assert_eq!(Generator::<u32>::balanced_chunks(7..8, 3), vec![7..8]);
+        assert_eq!(Generator::<u32>::balanced_chunks(5..5, 3), vec![]);
+        assert_eq!(Generator::<u32>::balanced_chunks(1..100, 0), vec![]);
+        let chunks = Generator::<u128>::balanced_chunks(1 << 100..(1 << 100) + 1000, 2);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].end, (1 << 100) + 1000);
+    }
+
+    #[test]
+    fn test_factored_numbers() {
+        let mut gener = Generator::<u64>::with_params(1_000_000_000, 1000, 100_000, false);
+        gener.aliquot_seq(12);
+        assert!(gener.factored_numbers().is_empty());
+        gener.track_factored(true);
+        for n in 1..1000 {
+            gener.aliquot_seq(n);
+        }
+        let factored = gener.factored_numbers().clone();
+        assert!(factored.contains(&999));
+        assert!(factored.contains(&284));
+        // 12 has been cached before the numbers were tracked
+        assert!(!factored.contains(&16));
+        // All sequences are taken from the cache during the second pass
+        for n in 1..1000 {
+            gener.aliquot_seq(n);
+        }
+        assert_eq!(gener.factored_numbers(), &factored);
+        gener.track_factored(false);
+        assert!(gener.factored_numbers().is_empty());
+    }
+
+    #[test]
+    fn test_annotated_terms() {
+        let aliquot_seq = AliquotSeq::<u32>::IntoCycle(vec![562], vec![284, 220]);
+        assert_eq!(
+            aliquot_seq.annotated_terms(),
+            vec![(0, 562, false), (1, 284, true), (2, 220, true)]
+        );
+        let mut gener = Generator::<u32>::new();
+        let flags = |aliquot_seq: AliquotSeq<u32>| {
+            aliquot_seq
+                .annotated_terms()
+                .into_iter()
+                .map(|(_, _, in_cycle)| in_cycle)
+                .collect::<Vec<bool>>()
+        };
+        assert_eq!(flags(gener.aliquot_seq(220)), vec![true, true]);
+        assert_eq!(flags(gener.aliquot_seq(12496)), vec![true; 5]);
+        assert_eq!(flags(gener.aliquot_seq(12)), vec![false; 7]);
+        assert_eq!(flags(gener.aliquot_seq(95)), vec![false; 3]);
+        assert_eq!(flags(gener.aliquot_seq(28)), vec![false]);
+        let annotated = gener.aliquot_seq(12).annotated_terms();
+        assert_eq!(annotated[1], (1, 16, false));
+    }
+
+    #[test]
+    fn test_terminal_prime() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(gener.aliquot_seq(12).terminal_prime(), Some(3));
+        assert_eq!(gener.aliquot_seq(7).terminal_prime(), Some(7));
+        assert_eq!(gener.aliquot_seq(10).terminal_prime(), Some(7));
+        assert_eq!(gener.aliquot_seq(28).terminal_prime(), None);
+        assert_eq!(gener.aliquot_seq(95).terminal_prime(), None);
+        assert_eq!(gener.aliquot_seq(220).terminal_prime(), None);
+        assert_eq!(gener.aliquot_seq(1).terminal_prime(), None);
+    }
+
+    #[test]
+    fn test_matches_type() {
+        let seqs = [
+            AliquotSeq::PerfectNumber(6u64),
+            AliquotSeq::PrimeNumber((7, 1)),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]),
+            AliquotSeq::AmicableNumber((220, 284)),
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+            AliquotSeq::AspiringNumber(vec![95, 25, 6]),
+            AliquotSeq::IntoCycle(vec![562], vec![284, 220]),
+            AliquotSeq::Unknown(
+                vec![276, 396],
+                UnknownMessage::new(UnknownReason::MaxValue, "Maximum value 400 exceeded"),
+            ),
+        ];
+        let kinds = [
+            "Perfect number",
+            "Prime number",
+            "Convergent sequence",
+            "Amicable number",
+            "Sociable number",
+            "Aspiring number",
+            "Convergent into cycle",
+            "Unknown sequence (Reason: Maximum value 400 exceeded)",
+        ];
+        for (i, aliquot_seq) in seqs.iter().enumerate() {
+            assert_eq!(aliquot_seq.type_str(), kinds[i]);
+            for (j, kind) in kinds.iter().enumerate() {
+                assert_eq!(aliquot_seq.matches_type(kind), i == j, "{kind}");
+            }
+            assert_eq!(aliquot_seq.is_aspiring(), i == 5);
+        }
+        assert!(seqs[7].matches_type("Unknown sequence"));
+        assert!(!seqs[7].matches_type("Unknown sequence (Reason: Interrupted)"));
+        assert!(!seqs[0].matches_type("Unknown sequence"));
+        assert!(!seqs[0].matches_type("perfect number"));
+        let mut gener = Generator::<u64>::new();
+        assert!(gener.aliquot_seq(95).is_aspiring());
+        assert!(!gener.aliquot_seq(6).is_aspiring());
+    }
+
+    #[test]
+    fn test_unknown_reason() {
+        let mut gener = Generator::<u64>::with_params(1_000_000, 5, 100, false);
+        assert_eq!(
+            gener.aliquot_seq(276).unknown_reason(),
+            Some(UnknownReason::MaxLength)
+        );
+        assert_eq!(
+            gener.aliquot_seq(1).unknown_reason(),
+            Some(UnknownReason::Undefined)
+        );
+        assert_eq!(gener.aliquot_seq(7).unknown_reason(), None);
+        let mut gener = Generator::<u64>::with_params(1000, 100, 100, false);
+        assert_eq!(
+            gener.aliquot_seq(138).unknown_reason(),
+            Some(UnknownReason::MaxValue)
+        );
+        let mut gener = Generator::<u16>::new();
+        assert_eq!(
+            gener.aliquot_seq(276).unknown_reason(),
+            Some(UnknownReason::Arithmetic)
+        );
+        let stop = AtomicBool::new(true);
+        assert_eq!(
+            gener.aliquot_seq_interruptible(138, &stop).unknown_reason(),
+            Some(UnknownReason::Interrupted)
+        );
+    }
+
+    #[test]
+    fn test_aliquot_sum_rem() {
+        // Sums all proper divisors using the former check i * (n / i) == n
+        fn reference<T: Number>(n: T) -> T {
+            iter_range(T::ONE..n)
+                .filter(|&i| i * (n / i) == n)
+                .fold(T::ZERO, |sum, i| sum + i)
+        }
+        for n in 0..2000u16 {
+            assert_eq!(Generator::<u16>::aliquot_sum(n), Ok(reference(n)), "{n}");
+        }
+        for n in (0..2000u64).chain([1264460, 1547860]) {
+            assert_eq!(Generator::<u64>::aliquot_sum(n), Ok(reference(n)), "{n}");
+        }
+    }
+
+    #[test]
+    fn test_divisor_pairs() {
+        assert_eq!(
+            Generator::<u32>::divisor_pairs(12),
+            Ok(vec![(1, 12), (2, 6), (3, 4)])
+        );
+        assert_eq!(
+            Generator::<u32>::divisor_pairs(36),
+            Ok(vec![(1, 36), (2, 18), (3, 12), (4, 9), (6, 6)])
+        );
+        assert_eq!(Generator::<u16>::divisor_pairs(1), Ok(vec![(1, 1)]));
+        assert_eq!(Generator::<u16>::divisor_pairs(97), Ok(vec![(1, 97)]));
+        assert!(Generator::<u64>::divisor_pairs(0).is_err());
+    }
+
+    #[test]
+    fn test_is_semiperfect() {
+        assert_eq!(Generator::<u32>::proper_divisors(1), Vec::<u32>::new());
+        assert_eq!(Generator::<u32>::proper_divisors(12), vec![1, 2, 3, 4, 6]);
+        assert_eq!(Generator::<u32>::proper_divisors(16), vec![1, 2, 4, 8]);
+        let mut gener = Generator::<u32>::new();
+        for n in [6, 12, 18, 20, 28, 88] {
+            assert_eq!(gener.is_semiperfect(n), Ok(true), "{n} is semiperfect");
+        }
+        for n in [0, 1, 7, 10, 70, 836] {
+            assert_eq!(gener.is_semiperfect(n), Ok(false), "{n} is not semiperfect");
+        }
+        // Large numbers with a small excess over n don't need a large table
+        let mut gener = Generator::<u64>::new();
+        let n = (1 << 20) * 2097143;
+        assert_eq!(Generator::<u64>::aliquot_sum(n).map(|sum| sum - n), Ok(8));
+        assert_eq!(gener.is_semiperfect(n), Ok(true));
+        assert!(matches!(
+            gener.is_semiperfect(720_720_000_000),
+            Err(AliquotError::InvalidArg(_))
+        ));
+    }
+
+    #[test]
+    fn test_aliquot_seq_interruptible() {
+        let mut gener = Generator::<u64>::new();
+        let stop = AtomicBool::new(false);
+        assert_eq!(
+            gener.aliquot_seq_interruptible(12, &stop),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1])
+        );
+        // Set the flag from another thread, while sequences are computed
+        let mut gener = Generator::<u64>::with_params(1_000_000_000_000, 1000, 1_000_000, false);
+        let interrupted = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                stop.store(true, AtomicOrdering::Relaxed);
+            });
+            (1..)
+                .map(|n| gener.aliquot_seq_interruptible(n, &stop))
+                .find(|aliquot_seq| {
+                    aliquot_seq.unknown_reason() == Some(UnknownReason::Interrupted)
+                })
+                .unwrap()
+        });
+        // The partial sequence is the start of the full one, but not cached
+        let n = interrupted.number();
+        let full = Generator::<u64>::with_params(1_000_000_000_000, 1000, 0, false).aliquot_seq(n);
+        assert!(full.seq().starts_with(&interrupted.seq()));
+        assert_eq!(gener.cache().get(n), None);
+        // Nothing is computed, once the flag is set
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(
+            gener.aliquot_seq_interruptible(276, &stop),
+            AliquotSeq::Unknown(
+                vec![276],
+                UnknownMessage::new(UnknownReason::Interrupted, "Interrupted")
+            )
+        );
+    }
+
+    #[test]
+    fn test_compare_maps() {
+        let mut gener = Generator::<u32>::with_params(1_000_000, 1000, 1000, false);
+        let sigma = |n: u32| Generator::<u32>::sigma(n);
+        assert_eq!(
+            gener.compare_maps(5..8, &AliquotSum, &sigma),
+            vec![
+                (5, Fate::Terminates, Fate::Open),
+                (6, Fate::Perfect, Fate::Open),
+                (7, Fate::Terminates, Fate::Open)
+            ]
+        );
+        let halve = |n: u32| -> Result<u32, AliquotError> { Ok(n / 2) };
+        assert_eq!(
+            gener.compare_maps(220..221, &AliquotSum, &halve),
+            vec![(220, Fate::Cycle, Fate::Terminates)]
+        );
+    }
+
+    #[test]
+    fn test_type_iterators() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(
+            gener.perfect_numbers(1..30).collect::<Vec<u32>>(),
+            vec![6, 28]
+        );
+        assert_eq!(
+            gener.primes(1..20).collect::<Vec<u32>>(),
+            vec![2, 3, 5, 7, 11, 13, 17, 19]
+        );
+        assert_eq!(
+            gener.amicable_numbers(200..300).collect::<Vec<u32>>(),
+            vec![220, 284]
+        );
+        assert_eq!(
+            gener.aspiring_numbers(1..100).collect::<Vec<u32>>(),
+            vec![25, 95]
+        );
+        assert_eq!(gener.sociable_numbers(1..100).count(), 0);
+        assert_eq!(
+            gener.primes(40..50).take(2).collect::<Vec<u32>>(),
+            vec![41, 43]
+        );
+    }
+
+    #[test]
+    fn test_cache_maps() {
+        let mut cache = Cache::<u32>::new(100);
+        cache.add(AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]));
+        cache.add(AliquotSeq::AmicableNumber((220, 284)));
+        assert_eq!(cache.sequences().len(), 3);
+        assert_eq!(
+            cache.sequences().get(&12),
+            Some(&AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]))
+        );
+        assert_eq!(
+            cache.sequences().get(&284),
+            Some(&AliquotSeq::AmicableNumber((284, 220)))
+        );
+        let mut lut = cache.lut().iter().collect::<Vec<(&u32, &u32)>>();
+        lut.sort();
+        assert_eq!(
+            lut,
+            vec![(&3, &12), (&4, &12), (&9, &12), (&15, &12), (&16, &12)]
+        );
+    }
+
+    #[test]
+    fn test_cache_with_max_bytes() {
+        assert_eq!(Cache::<u64>::capacity_for_bytes(32_000_000), 1_000_000);
+        assert_eq!(Cache::<u16>::capacity_for_bytes(32_000_000), 4_000_000);
+        assert_eq!(Cache::<u128>::capacity_for_bytes(63), 0);
+        let mut cache = Cache::<u32>::with_max_bytes(160);
+        cache.add(AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]));
+        assert_eq!(cache.n_seq(), 1);
+        cache.add(AliquotSeq::Convergent(vec![18, 21, 11, 1]));
+        assert_eq!(cache.n_seq(), 1);
+    }
+
+    #[test]
+    fn test_cache_seed() {
+        let mut cache = Cache::<u32>::new(1000);
+        cache.seed(30);
+        assert_eq!(cache.get(6), Some(AliquotSeq::PerfectNumber(6)));
+        assert_eq!(cache.get(7), Some(AliquotSeq::PrimeNumber((7, 1))));
+        assert_eq!(cache.get(28), Some(AliquotSeq::PerfectNumber(28)));
+        assert_eq!(cache.get(29), Some(AliquotSeq::PrimeNumber((29, 1))));
+        assert_eq!(cache.get(12), None);
+        assert_eq!(cache.get(31), None);
+        assert_eq!(cache.n_seq(), 12);
+        let mut gener = Generator::<u32>::new();
+        gener.cache_mut().seed(30);
+        test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
         test_gen(
             &mut gener,
-            1264460,
-            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+            12,
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]),
+        );
+    }
+
+    #[test]
+    fn test_warmup() {
+        let mut gener = Generator::<u64>::with_params(1_000_000_000, 1000, 1_000_000, false);
+        gener.warmup(1000);
+        assert!(iter_range(2..1000).all(|n| gener.cache().get(n).is_some()));
+        // 1001 = 7 * 11 * 13 has the aliquot sum 343, whose sequence is cached
+        gener.track_factored(true);
+        test_gen(
+            &mut gener,
+            1001,
+            AliquotSeq::Convergent(vec![1001, 343, 57, 23, 1]),
+        );
+        assert_eq!(gener.factored_numbers(), &HashSet::from([1001]));
+    }
+
+    #[test]
+    fn test_cache_is_in_cycle() {
+        let mut gener = Generator::<u32>::new();
+        gener.aliquot_seq(1264460);
+        gener.aliquot_seq(220);
+        gener.aliquot_seq(12);
+        let cache = gener.cache();
+        for n in [1264460, 1547860, 1727636, 1305184, 220, 284] {
+            assert!(cache.is_in_cycle(n));
+        }
+        for n in [12, 16, 1, 6, 1264461] {
+            assert!(!cache.is_in_cycle(n));
+        }
+        let mut cache = Cache::<u32>::new(1000);
+        cache.add(AliquotSeq::IntoCycle(vec![10, 20], vec![30, 40, 50]));
+        assert!(!cache.is_in_cycle(10));
+        assert!(!cache.is_in_cycle(20));
+        assert!(cache.is_in_cycle(40));
+        // Members of evicted cycles are removed
+        let mut cache = Cache::<u32>::with_policy(12, CachePolicy::Lru);
+        cache.add(AliquotSeq::AmicableNumber((220, 284)));
+        cache.add(AliquotSeq::SociableNumber(vec![
+            1264460, 1547860, 1727636, 1305184,
+        ]));
+        assert!(cache.is_in_cycle(1305184));
+        assert!(cache.is_in_cycle(284));
+        cache.add(AliquotSeq::Convergent(vec![40, 50, 43, 1]));
+        cache.add(AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]));
+        assert!(!cache.is_in_cycle(220));
+        assert!(!cache.is_in_cycle(284));
+        cache.clear();
+        assert!(!cache.is_in_cycle(1264460));
+    }
+
+    #[test]
+    fn test_cache_iter() {
+        let mut gener = Generator::<u32>::new();
+        for n in [95, 12, 220, 1264460, 7, 6] {
+            gener.aliquot_seq(n);
+        }
+        let keys = gener.cache().iter().map(|(n, _)| *n).collect::<Vec<u32>>();
+        assert_eq!(keys, vec![6, 7, 12, 95, 220, 284, 1264460]);
+        let (n, aliquot_seq) = gener.cache().iter().nth(3).unwrap();
+        assert_eq!(*n, 95);
+        assert_eq!(*aliquot_seq, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut gener = Generator::<u32>::new();
+        for n in [1, 6, 7, 12, 95, 220, 284, 562, 1264460] {
+            let aliquot_seq = gener.aliquot_seq(n);
+            assert_eq!(gener.verify(n, &aliquot_seq), Ok(()));
+        }
+        let aliquot_seq = gener.aliquot_seq(12);
+        assert!(gener.verify(13, &aliquot_seq).is_err());
+        let invalid = [
+            AliquotSeq::PerfectNumber(12),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3]),
+            AliquotSeq::Convergent(vec![12, 15, 9, 4, 3, 1]),
+            AliquotSeq::AmicableNumber((220, 220)),
+            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636]),
+            AliquotSeq::AspiringNumber(vec![95, 25]),
+            AliquotSeq::IntoCycle(vec![12], vec![16, 15]),
+        ];
+        for aliquot_seq in invalid {
+            assert!(gener.verify(aliquot_seq.number(), &aliquot_seq).is_err());
+        }
+    }
+
+    #[test]
+    fn test_term_count_estimate() {
+        let mut gener = Generator::<u32>::new();
+        // 2, 3, 5, 7 and 11 are primes and 6 is perfect
+        // 4: [4, 3, 1], 8: [8, 7, 1], 9: [9, 4, 3, 1], 10: [10, 8, 7, 1]
+        // 12: [12, 16, 15, 9, 4, 3, 1]
+        assert_eq!(
+            gener.term_count_estimate(2..13),
+            5 * 2 + 1 + 3 + 3 + 4 + 4 + 7
+        );
+        assert_eq!(gener.term_count_estimate(220..221), 2);
+        assert_eq!(gener.term_count_estimate(5..5), 0);
+    }
+
+    #[test]
+    fn test_cycle_order_histogram() {
+        assert_eq!(
+            AliquotSeq::<u32>::AmicableNumber((220, 284)).cycle_length(),
+            Some(2)
+        );
+        assert_eq!(AliquotSeq::<u32>::PerfectNumber(6).cycle_length(), None);
+        let mut gener = Generator::<u32>::with_params(1_000_000_000, 1000, 1_000_000, false);
+        // 220 and 284 are amicable, 562 runs into their cycle
+        assert_eq!(
+            gener.cycle_order_histogram(200..600),
+            BTreeMap::from([(2, 3)])
+        );
+        assert_eq!(
+            gener.cycle_order_histogram(1264460..1264461),
+            BTreeMap::from([(4, 1)])
+        );
+        assert_eq!(
+            gener.cycle_order_histogram(12496..12497),
+            BTreeMap::from([(5, 1)])
+        );
+        assert!(gener.cycle_order_histogram(1..200).is_empty());
+    }
+
+    #[test]
+    fn test_amicable_pairs_filtered() {
+        let mut gener = Generator::<u32>::new();
+        let pairs = gener.amicable_pairs(1..13000);
+        assert_eq!(pairs.len(), 7);
+        assert_eq!(pairs[6], (12285, 14595));
+        // All pairs except the odd pair 12285, 14595 are even
+        let even = gener.amicable_pairs_filtered(1..13000, |n| n % 2 == 0);
+        assert_eq!(even, pairs[..6].to_vec());
+        assert!(even.contains(&(220, 284)));
+        // 1210 is even, but not divisible by four
+        let div_by_four = gener.amicable_pairs_filtered(1..13000, |n| n % 4 == 0);
+        assert_eq!(div_by_four.len(), 5);
+        assert!(!div_by_four.contains(&(1184, 1210)));
+        assert_eq!(gener.amicable_pairs_filtered(1..13000, |_| false), vec![]);
+    }
+
+    #[test]
+    fn test_k_step_amicable() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(
+            gener.k_step_amicable(1..10000, 1),
+            vec![
+                (220, 284),
+                (1184, 1210),
+                (2620, 2924),
+                (5020, 5564),
+                (6232, 6368)
+            ]
+        );
+        // The partner may be outside of the range
+        assert_eq!(gener.k_step_amicable(200..250, 1), vec![(220, 284)]);
+        assert_eq!(gener.k_step_amicable(250..300, 1), vec![]);
+        assert_eq!(gener.k_step_amicable(1..300, 2), vec![]);
+        // Members of a cycle of four numbers two steps apart
+        assert_eq!(
+            gener.k_step_amicable(1264460..1264461, 2),
+            vec![(1264460, 1727636)]
+        );
+        assert_eq!(
+            gener.k_step_amicable(1305184..1305185, 2),
+            vec![(1305184, 1547860)]
+        );
+        assert_eq!(gener.k_step_amicable(1..10, 0), vec![]);
+    }
+
+    #[test]
+    fn test_first_escaping() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(gener.first_escaping(1..200, 1_000_000), Some(138));
+        assert_eq!(gener.first_escaping(200..300, 1_000_000), Some(222));
+        assert_eq!(gener.first_escaping(270..300, 1_000_000), Some(276));
+        assert_eq!(gener.first_escaping(1..100, 1_000_000), None);
+        // The sequence of 12 is cached, but 16 exceeds the bound
+        gener.aliquot_seq(12);
+        assert_eq!(gener.first_escaping(12..13, 15), Some(12));
+        assert_eq!(gener.first_escaping(12..13, 16), None);
+        // Overflowing sequences exceed any bound
+        let mut gener = Generator::<u16>::new();
+        assert_eq!(gener.first_escaping(270..280, u16::MAX), Some(276));
+    }
+
+    #[test]
+    fn test_cache_policy() {
+        let short = [7, 11, 13].map(|p| AliquotSeq::<u32>::PrimeNumber((p, 1)));
+        let seq_12 = AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]);
+        let seq_95 = AliquotSeq::AspiringNumber(vec![95, 25, 6]);
+        let fill = |policy| {
+            let mut cache = Cache::with_policy(20, policy);
+            for aliquot_seq in short.iter().chain([&seq_12]) {
+                cache.add(aliquot_seq.clone());
+            }
+            cache
+        };
+        // The new sequence is rejected by default
+        let mut cache = fill(CachePolicy::RejectNew);
+        cache.add(seq_95.clone());
+        assert_eq!(cache.n_seq(), 4);
+        assert_eq!(cache.get(95), None);
+        // The shortest sequences are evicted first
+        let mut cache = fill(CachePolicy::KeepLongest);
+        cache.add(seq_95.clone());
+        assert_eq!(cache.n_seq(), 3);
+        assert_eq!(cache.get(12), Some(seq_12.clone()));
+        assert_eq!(cache.get(95), Some(seq_95.clone()));
+        assert_eq!(
+            cache.get(16),
+            Some(AliquotSeq::Convergent(vec![16, 15, 9, 4, 3, 1]))
+        );
+        // No sequence is evicted for a sequence, which is not longer
+        cache.add(AliquotSeq::PrimeNumber((17, 1)));
+        assert_eq!(cache.get(17), None);
+        let seq_30 = Generator::<u32>::new().aliquot_seq(30);
+        cache.add(seq_30.clone());
+        assert_eq!(cache.n_seq(), 1);
+        assert_eq!(cache.get(30), Some(seq_30));
+        assert_eq!(cache.get(16), None);
+        // The least recently used sequences are evicted first
+        let mut cache = Cache::with_policy(20, CachePolicy::Lru);
+        for aliquot_seq in short.iter() {
+            cache.add(aliquot_seq.clone());
+        }
+        assert!(cache.get(7).is_some());
+        cache.add(seq_12.clone());
+        cache.add(seq_95.clone());
+        let mut keys = cache.sequences().keys().copied().collect::<Vec<u32>>();
+        keys.sort();
+        assert_eq!(keys, vec![7, 12, 95]);
+        // Using an amicable number keeps both of its entries
+        let mut cache = Cache::<u32>::with_policy(6, CachePolicy::Lru);
+        cache.add(AliquotSeq::AmicableNumber((220, 284)));
+        cache.add(AliquotSeq::PrimeNumber((7, 1)));
+        assert!(cache.get(220).is_some());
+        cache.add(AliquotSeq::PrimeNumber((11, 1)));
+        assert_eq!(cache.get(7), None);
+        assert_eq!(cache.get(284), Some(AliquotSeq::AmicableNumber((284, 220))));
+        assert!(cache.get(11).is_some());
+        assert_eq!(
+            CachePolicy::from_str("keep-longest"),
+            Ok(CachePolicy::KeepLongest)
+        );
+        assert!(CachePolicy::from_str("random").is_err());
+    }
+
+    #[test]
+    fn test_cache_get_with_origin() {
+        let mut gener = Generator::<u32>::new();
+        let seq_12 = gener.aliquot_seq(12);
+        gener.aliquot_seq(220);
+        let cache = gener.cache();
+        assert_eq!(
+            cache.get_with_origin(12),
+            Some((seq_12, CacheOrigin::Direct))
+        );
+        let (aliquot_seq, origin) = cache.get_with_origin(15).unwrap();
+        assert_eq!(aliquot_seq, AliquotSeq::Convergent(vec![15, 9, 4, 3, 1]));
+        assert_eq!(
+            origin,
+            CacheOrigin::Reconstructed {
+                parent: 12,
+                offset: 2
+            }
+        );
+        let (aliquot_seq, origin) = cache.get_with_origin(3).unwrap();
+        assert_eq!(aliquot_seq, AliquotSeq::PrimeNumber((3, 1)));
+        assert_eq!(
+            origin,
+            CacheOrigin::Reconstructed {
+                parent: 12,
+                offset: 5
+            }
+        );
+        // Amicable numbers are stored in both orders
+        assert_eq!(
+            cache.get_with_origin(284),
+            Some((AliquotSeq::AmicableNumber((284, 220)), CacheOrigin::Direct))
+        );
+        assert_eq!(cache.get_with_origin(1), None);
+        assert_eq!(cache.get_with_origin(13), None);
+    }
+
+    #[test]
+    fn test_cache_compact() {
+        let mut reference = Generator::<u32>::with_params(1_000_000, 1000, 0, false);
+        let mut gener = Generator::<u32>::with_params(1_000_000, 1000, 100, false);
+        gener.cache_mut().set_policy(CachePolicy::Lru);
+        for n in 2..300 {
+            gener.aliquot_seq(n);
+        }
+        let cache = gener.cache_mut();
+        // Orphaned entries of an evicted sequence
+        let evicted = (2..300).find(|n| cache.get(*n).is_none()).unwrap();
+        cache.cache_lut.insert(1000, evicted);
+        cache.cache_lut.insert(evicted, 12);
+        let count = cache.count();
+        cache.compact();
+        assert_eq!(cache.count(), count);
+        assert!(!cache.lut().contains_key(&1000));
+        assert!(
+            cache
+                .lut()
+                .values()
+                .all(|p| cache.sequences().contains_key(p))
+        );
+        for n in 2..300 {
+            match cache.get(n) {
+                Some(aliquot_seq) => assert_eq!(aliquot_seq, reference.aliquot_seq(n)),
+                None => assert!(!cache.lut().contains_key(&n)),
+            }
+        }
+        assert_eq!(cache.get(evicted), None);
+        // Compacting a consistent cache doesn't change it
+        let lut = cache.lut().clone();
+        cache.compact();
+        assert_eq!(cache.lut().len(), lut.len());
+        assert_eq!(cache.count(), count);
+    }
+
+    #[test]
+    fn test_cache_cycles() {
+        let mut gener = Generator::<u32>::with_params(1_000_000_000, 1000, 1_000_000, false);
+        for n in (200..300).chain([284, 1264460, 1305184]) {
+            gener.aliquot_seq(n);
+        }
+        assert_eq!(
+            gener.cache().cycles(),
+            vec![
+                AliquotSeq::AmicableNumber((220, 284)),
+                AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+            ]
+        );
+        assert_eq!(Generator::<u32>::new().cache().cycles(), vec![]);
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_log_records() {
+        use std::sync::Mutex;
+
+        struct CaptureLogger(Mutex<Vec<String>>);
+
+        impl log::Log for CaptureLogger {
+            fn enabled(&self, _metadata: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                if let Ok(mut records) = self.0.lock() {
+                    records.push(format!("{} {}", record.level(), record.args()));
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CaptureLogger = CaptureLogger(Mutex::new(vec![]));
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+        let mut gener = Generator::<u32>::new();
+        gener.aliquot_seq(12);
+        gener.aliquot_seq(16);
+        gener.aliquot_seq(21);
+        gener.aliquot_seq(18);
+        // Errors are logged instead of printed to stdout
+        Generator::<u16>::new().aliquot_seq(276);
+        let records = LOGGER.0.lock().unwrap();
+        let warning = "WARN Sequence of 276 unknown, because an error occurred";
+        assert!(records.iter().any(|record| record.starts_with(warning)));
+        for line in [
+            "DEBUG Sequence for 12 converged to one",
+            "TRACE Found sequence for 16 in the cache",
+            "TRACE Found sequence for 21 in the cache to complete the sequence for 18",
+        ] {
+            assert!(records.contains(&line.to_string()), "missing record {line}");
+        }
+    }
+
+    #[test]
+    fn test_is_perfect_even() {
+        let mut gener = Generator::<u16>::new();
+        assert!(gener.is_perfect(8128).unwrap());
+        // The divisors of 65520 sum up to more than the maximum of u16
+        assert!(Generator::<u16>::aliquot_sum(65520).is_err());
+        assert!(!gener.is_perfect(65520).unwrap());
+        // 2^10 * (2^11 - 1), but 2^11 - 1 = 23 * 89 isn't prime
+        assert!(!Generator::<u32>::new().is_perfect(2096128).unwrap());
+        // Too large for summing up the divisors by trial division in time
+        let mut gener = Generator::<u64>::new();
+        assert!(gener.is_perfect(2305843008139952128).unwrap());
+        assert!(!gener.is_perfect(2305843008139952126).unwrap());
+        let mut gener = Generator::<u128>::new();
+        assert!(gener.is_perfect((1 << 60) * ((1 << 61) - 1)).unwrap());
+        assert!(!gener.is_perfect(1 << 127).unwrap());
+        let perfect = (1..10000u32)
+            .filter(|&n| Generator::<u32>::new().is_perfect(n).unwrap())
+            .collect::<Vec<u32>>();
+        assert_eq!(perfect, vec![6, 28, 496, 8128]);
+    }
+
+    #[test]
+    fn test_aliquot_residue() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.aliquot_residue(12), Ok((NumberClass::Abundant, 4)));
+        assert_eq!(gener.aliquot_residue(945), Ok((NumberClass::Abundant, 30)));
+        assert_eq!(gener.aliquot_residue(6), Ok((NumberClass::Perfect, 0)));
+        assert_eq!(gener.aliquot_residue(8128), Ok((NumberClass::Perfect, 0)));
+        assert_eq!(gener.aliquot_residue(7), Ok((NumberClass::Deficient, 6)));
+        // Powers of two are almost perfect
+        assert_eq!(gener.aliquot_residue(1024), Ok((NumberClass::Deficient, 1)));
+        assert_eq!(gener.aliquot_residue(1), Ok((NumberClass::Deficient, 1)));
+        assert!(gener.aliquot_residue(0).is_err());
+        assert!(Generator::<u16>::new().aliquot_residue(65520).is_err());
+    }
+
+    #[test]
+    fn test_is_sociable() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(gener.is_sociable(6, 1), Ok(true));
+        assert_eq!(gener.is_sociable(220, 2), Ok(true));
+        assert_eq!(gener.is_sociable(284, 2), Ok(true));
+        assert_eq!(gener.is_sociable(1264460, 4), Ok(true));
+        assert_eq!(gener.is_sociable(1547860, 4), Ok(true));
+        assert_eq!(gener.is_sociable(12496, 5), Ok(true));
+        assert_eq!(gener.is_sociable(220, 4), Ok(false));
+        assert_eq!(gener.is_sociable(6, 2), Ok(false));
+        assert_eq!(gener.is_sociable(1264460, 2), Ok(false));
+        // 562 runs into the amicable pair 284, 220 without being a member
+        assert_eq!(gener.is_sociable(562, 2), Ok(false));
+        assert_eq!(gener.is_sociable(12, 1), Ok(false));
+        assert!(gener.is_sociable(220, 0).is_err());
+    }
+
+    #[test]
+    fn test_quick_fate() {
+        let mut gener = Generator::<u64>::new();
+        assert!(gener.is_prime(97).unwrap());
+        assert!(!gener.is_prime(1).unwrap());
+        assert!(!gener.is_prime(91).unwrap());
+        assert!(gener.is_perfect(8128).unwrap());
+        assert!(!gener.is_perfect(0).unwrap());
+        assert!(!gener.is_perfect(12).unwrap());
+        assert!(!gener.is_perfect(945).unwrap());
+        assert_eq!(gener.quick_fate(97), Ok(Fate::Terminates));
+        assert_eq!(gener.quick_fate(12), Ok(Fate::Terminates));
+        assert_eq!(gener.quick_fate(28), Ok(Fate::Perfect));
+        assert_eq!(gener.quick_fate(95), Ok(Fate::Perfect));
+        assert_eq!(gener.quick_fate(220), Ok(Fate::Cycle));
+        assert_eq!(gener.quick_fate(12496), Ok(Fate::Cycle));
+        assert_eq!(gener.quick_fate(276), Ok(Fate::Open));
+        // The fate is taken from the cache as soon as possible
+        gener.aliquot_seq(138);
+        assert_eq!(gener.quick_fate(138), Ok(Fate::Terminates));
+        for n in 2..300 {
+            let expected = match n {
+                276 => Fate::Open,
+                _ => gener.aliquot_seq(n).fate(),
+            };
+            assert_eq!(gener.quick_fate(n), Ok(expected), "fate of {n}");
+        }
+    }
+
+    #[test]
+    fn test_detects_cycle() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(gener.detects_cycle(220, 100), Ok(Some(true)));
+        assert_eq!(gener.detects_cycle(12496, 100), Ok(Some(true)));
+        assert_eq!(gener.detects_cycle(562, 100), Ok(Some(true)));
+        assert_eq!(gener.detects_cycle(7, 100), Ok(Some(false)));
+        assert_eq!(gener.detects_cycle(12, 100), Ok(Some(false)));
+        assert_eq!(gener.detects_cycle(6, 100), Ok(Some(false)));
+        assert_eq!(gener.detects_cycle(95, 100), Ok(Some(false)));
+        assert_eq!(gener.detects_cycle(1, 100), Ok(Some(false)));
+        assert_eq!(gener.detects_cycle(276, 10), Ok(None));
+    }
+
+    #[test]
+    fn test_classify_census() {
+        assert_eq!(Generator::<u32>::classify_census(1..101), (22, 76, 2));
+        assert_eq!(Generator::<u32>::classify_census(5..5), (0, 0, 0));
+        let mut gener = Generator::<u16>::new();
+        let range = 60000..u16::MAX;
+        let classes = range
+            .clone()
+            .map(|n| gener.classify_number(n).unwrap_or(NumberClass::Abundant))
+            .collect::<Vec<NumberClass>>();
+        let count = |class| classes.iter().filter(|&&c| c == class).count();
+        assert_eq!(
+            Generator::<u16>::classify_census(range),
+            (
+                count(NumberClass::Abundant),
+                count(NumberClass::Deficient),
+                count(NumberClass::Perfect)
+            )
+        );
+    }
+
+    #[test]
+    fn test_classify_number() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.classify_number(1), Ok(NumberClass::Deficient));
+        assert_eq!(gener.classify_number(7), Ok(NumberClass::Deficient));
+        assert_eq!(gener.classify_number(28), Ok(NumberClass::Perfect));
+        assert_eq!(gener.classify_number(12), Ok(NumberClass::Abundant));
+        assert_eq!(gener.classify_number(945), Ok(NumberClass::Abundant));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_classify_range() {
+        let mut gener = Generator::<u64>::new();
+        let serial = (1..10_000)
+            .map(|n| gener.classify_number(n).unwrap())
+            .collect::<Vec<NumberClass>>();
+        assert_eq!(gener.classify_range(1..10_000), Ok(serial));
+        assert_eq!(gener.classify_range(5..5), Ok(vec![]));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_aliquot_stream() {
+        use tokio_stream::StreamExt;
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let streamed = runtime.block_on(async {
+            Generator::<u32>::new()
+                .aliquot_stream(1..300)
+                .collect::<Vec<(u32, AliquotSeq<u32>)>>()
+                .await
+        });
+        let mut gener = Generator::<u32>::new();
+        let serial = (1..300)
+            .map(|n| (n, gener.aliquot_seq(n)))
+            .collect::<Vec<(u32, AliquotSeq<u32>)>>();
+        assert_eq!(streamed, serial);
+    }
+
+    #[test]
+    fn test_aliquot_seq_sieve() {
+        let mut gener = Generator::<u64>::new();
+        let mut gener_sieve = Generator::<u64>::new();
+        gener_sieve.set_sieve(Some(Sieve::new(1000)));
+        for n in (1..276).chain([1264460]) {
+            assert_eq!(gener_sieve.aliquot_seq(n), gener.aliquot_seq(n));
+        }
+    }
+
+    #[test]
+    fn test_aliquot_sum_sieve() {
+        let sums = Generator::<u64>::aliquot_sum_sieve(0..2000).unwrap();
+        for (n, sum) in (0..2000).zip(sums) {
+            assert_eq!(sum, Generator::<u64>::aliquot_sum(n).unwrap());
+        }
+        let sums = Generator::<u64>::aliquot_sum_sieve(1264000..1265000).unwrap();
+        for (n, sum) in (1264000..1265000).zip(sums) {
+            assert_eq!(sum, Generator::<u64>::aliquot_sum(n).unwrap());
+        }
+        // Some aliquot sums close to the maximum of u16 overflow
+        assert!(Generator::<u16>::aliquot_sum(65520).is_err());
+        assert!(Generator::<u16>::aliquot_sum_sieve(65000..u16::MAX).is_err());
+        let sums = Generator::<u16>::aliquot_sum_sieve(65533..u16::MAX).unwrap();
+        for (n, sum) in (65533..u16::MAX).zip(sums) {
+            assert_eq!(sum, Generator::<u16>::aliquot_sum(n).unwrap());
+        }
+        assert!(
+            Generator::<u16>::aliquot_sum_sieve(7..7)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_aliquot_sum_batch() {
+        let ns = [
+            1264460, 12, 0, 220, 1, 6, 284, 97, 12, 5000000, 138, 1000000007, 300000, 1264461,
+        ];
+        let sums = Generator::<u64>::aliquot_sum_batch(&ns).unwrap();
+        assert_eq!(sums.len(), ns.len());
+        for (n, sum) in ns.iter().zip(sums) {
+            assert_eq!(sum, Generator::<u64>::aliquot_sum(*n).unwrap());
+        }
+        let ns = [u16::MAX, 2, 65533, 1000];
+        let sums = Generator::<u16>::aliquot_sum_batch(&ns).unwrap();
+        assert_eq!(sums, vec![45921, 1, 6049, 1340]);
+        // Scattered numbers and duplicates within a narrow segment
+        let ns = [
+            1_000_000_000_005u64,
+            7,
+            1_000_000_000_000,
+            2_000_000_000_000,
+            7,
+        ];
+        let sums = Generator::<u64>::aliquot_sum_batch(&ns).unwrap();
+        for (n, sum) in ns.iter().zip(sums) {
+            assert_eq!(sum, Generator::<u64>::aliquot_sum(*n).unwrap());
+        }
+        assert!(Generator::<u16>::aliquot_sum_batch(&[65520]).is_err());
+        assert!(Generator::<u16>::aliquot_sum_batch(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_abundant_bitset() {
+        let abundant = Generator::<u32>::abundant_bitset(1..100);
+        for n in [
+            12, 18, 20, 24, 30, 36, 40, 42, 48, 54, 56, 60, 66, 70, 72, 78, 80, 84, 88, 90, 96,
+        ] {
+            assert!(abundant.contains(n), "{n} is abundant");
+        }
+        for n in [0, 1, 6, 8, 28, 97, 100, 102] {
+            assert!(!abundant.contains(n), "{n} is not abundant");
+        }
+        assert_eq!(abundant.count(), 21);
+        let abundant = Generator::<u16>::abundant_bitset(65500..u16::MAX);
+        assert!(abundant.contains(65520));
+        assert!(!abundant.contains(65521));
+    }
+
+    #[test]
+    fn test_preimages() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.preimages(6, Some(1000)), Ok(vec![6, 25]));
+        assert_eq!(
+            gener.preimages(1, Some(30)),
+            Ok(vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29])
         );
-        test_gen(
-            &mut gener,
-            276,
-            AliquotSeq::Unknown(
-                vec![
-                    276, 396, 696, 1104, 1872, 3770, 3790, 3050, 2716, 2772, 5964, 10164, 19628,
-                    19684, 22876, 26404, 30044, 33796, 38780, 54628, 54684, 111300, 263676, 465668,
-                    465724, 465780, 1026060, 2325540, 5335260, 11738916, 23117724, 45956820,
-                    121129260, 266485716, 558454764, 1092873236, 1470806764, 1471882804,
-                    1642613196, 2737688884, 2740114636, 2791337780,
-                ],
-                "Overflow error: 4213448791 plus 99690663 exceeds maximum 4294967295".to_string(),
-            ),
+        assert_eq!(gener.preimages(284, Some(220)), Ok(vec![220]));
+        assert_eq!(gener.preimages(284, Some(219)), Ok(Vec::<u32>::new()));
+        assert_eq!(gener.in_degree(5, 1000), 0);
+        assert_eq!(gener.in_degree(52, 10000), 0);
+        assert_eq!(gener.in_degree(6, 1000), 2);
+        assert_eq!(gener.preimages(16, Some(1000)), Ok(vec![12, 26]));
+        assert_eq!(gener.in_degree(16, 1000), 2);
+        assert!(gener.in_degree(43, 1000) > 0);
+        let mut gener = Generator::<u16>::new();
+        assert_eq!(
+            gener.preimages(1, Some(u16::MAX)).unwrap().last(),
+            Some(&65521)
         );
+        assert_eq!(gener.in_degree(1, u16::MAX), 6542);
     }
 
     #[test]
-    fn test_aliquot_seq_u64() {
+    fn test_next_untouchable() {
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.next_untouchable(2, 10000), Some(5));
+        assert_eq!(gener.next_untouchable(0, 10000), Some(2));
+        assert_eq!(gener.next_untouchable(5, 10000), Some(52));
+        assert_eq!(gener.next_untouchable(52, 100000), Some(88));
+        let mut gener = Generator::<u16>::new();
+        assert_eq!(gener.next_untouchable(u16::MAX, 100), None);
+    }
+
+    #[test]
+    fn test_safe_preimage_bound() {
+        assert_eq!(Generator::<u32>::safe_preimage_bound(0), Some(1));
+        assert_eq!(Generator::<u32>::safe_preimage_bound(6), Some(25));
+        assert_eq!(Generator::<u16>::safe_preimage_bound(1000), Some(u16::MAX));
+        let mut gener = Generator::<u32>::new();
+        assert_eq!(gener.preimages(0, None), Ok(vec![1]));
+        assert_eq!(gener.preimages(6, None), Ok(vec![6, 25]));
+        // The square of the prime 7 is the largest preimage of 8
+        assert_eq!(gener.preimages(8, None), Ok(vec![10, 49]));
+        // No preimage is beyond the bound
+        for n in 2..100 {
+            assert_eq!(gener.preimages(n, None), gener.preimages(n, Some(10_000)));
+        }
+        // All primes are preimages of one, so a bound is required
+        let mut gener = Generator::<u16>::new();
+        assert_eq!(Generator::<u16>::safe_preimage_bound(1), None);
+        assert!(gener.preimages(1, None).is_err());
+        let primes = gener.preimages(1, Some(u16::MAX)).unwrap();
+        assert_eq!(primes.len(), 6542);
+        assert!(primes.iter().all(|&p| gener.is_prime(p).unwrap()));
+        // The automatic bound of large numbers is rejected instead of searching for hours
         let mut gener = Generator::<u64>::new();
-        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
-        test_gen(&mut gener, 3, AliquotSeq::PrimeNumber((3, 1)));
-        test_gen(&mut gener, 6, AliquotSeq::PerfectNumber(6));
-        test_gen(&mut gener, 17, AliquotSeq::PrimeNumber((17, 1)));
-        test_gen(&mut gener, 19, AliquotSeq::PrimeNumber((19, 1)));
-        test_gen(&mut gener, 41, AliquotSeq::PrimeNumber((41, 1)));
-        test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
-        test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
-        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
-        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
-        test_gen(
-            &mut gener,
-            1264460,
-            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
-        );
-        test_gen(
-            &mut gener,
-            138,
-            AliquotSeq::Convergent(vec![
-                138,
-                150,
-                222,
-                234,
-                312,
-                528,
-                960,
-                2088,
-                3762,
-                5598,
-                6570,
-                10746,
-                13254,
-                13830,
-                19434,
-                20886,
-                21606,
-                25098,
-                26742,
-                26754,
-                40446,
-                63234,
-                77406,
-                110754,
-                171486,
-                253458,
-                295740,
-                647748,
-                1077612,
-                1467588,
-                1956812,
-                2109796,
-                1889486,
-                953914,
-                668966,
-                353578,
-                176792,
-                254128,
-                308832,
-                502104,
-                753216,
-                1240176,
-                2422288,
-                2697920,
-                3727264,
-                3655076,
-                2760844,
-                2100740,
-                2310856,
-                2455544,
-                3212776,
-                3751064,
-                3282196,
-                2723020,
-                3035684,
-                2299240,
-                2988440,
-                5297320,
-                8325080,
-                11222920,
-                15359480,
-                19199440,
-                28875608,
-                25266172,
-                19406148,
-                26552604,
-                40541052,
-                54202884,
-                72270540,
-                147793668,
-                228408732,
-                348957876,
-                508132204,
-                404465636,
-                303708376,
-                290504024,
-                312058216,
-                294959384,
-                290622016,
-                286081174,
-                151737434,
-                75868720,
-                108199856,
-                101437396,
-                76247552,
-                76099654,
-                42387146,
-                21679318,
-                12752594,
-                7278382,
-                3660794,
-                1855066,
-                927536,
-                932464,
-                1013592,
-                1546008,
-                2425752,
-                5084088,
-                8436192,
-                13709064,
-                20563656,
-                33082104,
-                57142536,
-                99483384,
-                245978376,
-                487384824,
-                745600776,
-                1118401224,
-                1677601896,
-                2538372504,
-                4119772776,
-                8030724504,
-                14097017496,
-                21148436904,
-                40381357656,
-                60572036544,
-                100039354704,
-                179931895322,
-                94685963278,
-                51399021218,
-                28358080762,
-                18046051430,
-                17396081338,
-                8698040672,
-                8426226964,
-                6319670230,
-                5422685354,
-                3217383766,
-                1739126474,
-                996366646,
-                636221402,
-                318217798,
-                195756362,
-                101900794,
-                54202694,
-                49799866,
-                24930374,
-                17971642,
-                11130830,
-                8904682,
-                4913018,
-                3126502,
-                1574810,
-                1473382,
-                736694,
-                541162,
-                312470,
-                249994,
-                127286,
-                69898,
-                34952,
-                34708,
-                26038,
-                13994,
-                7000,
-                11720,
-                14740,
-                19532,
-                16588,
-                18692,
-                14026,
-                7016,
-                6154,
-                3674,
-                2374,
-                1190,
-                1402,
-                704,
-                820,
-                944,
-                916,
-                694,
-                350,
-                394,
-                200,
-                265,
-                59,
-                1,
-            ]),
-        );
+        assert_eq!(Generator::<u64>::safe_preimage_bound(32769), Some(1 << 30));
+        assert!(gener.preimages(32770, None).is_err());
+        assert_eq!(gener.preimages(100_000, Some(100)), Ok(Vec::<u64>::new()));
+        assert!(gener.preimages(100_000, None).is_err());
     }
 
     #[test]
-    fn test_aliquot_seq_u128() {
-        let mut gener = Generator::<u128>::new();
-        test_gen(&mut gener, 2, AliquotSeq::PrimeNumber((2, 1)));
-        test_gen(&mut gener, 3, AliquotSeq::PrimeNumber((3, 1)));
-        test_gen(&mut gener, 6, AliquotSeq::PerfectNumber(6));
-        test_gen(&mut gener, 17, AliquotSeq::PrimeNumber((17, 1)));
-        test_gen(&mut gener, 19, AliquotSeq::PrimeNumber((19, 1)));
-        test_gen(&mut gener, 41, AliquotSeq::PrimeNumber((41, 1)));
-        test_gen(&mut gener, 43, AliquotSeq::PrimeNumber((43, 1)));
-        test_gen(&mut gener, 95, AliquotSeq::AspiringNumber(vec![95, 25, 6]));
-        test_gen(&mut gener, 220, AliquotSeq::AmicableNumber((220, 284)));
-        test_gen(&mut gener, 284, AliquotSeq::AmicableNumber((284, 220)));
-        test_gen(
-            &mut gener,
-            1264460,
-            AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
-        );
+    fn test_offset() {
+        assert_eq!(Generator::<u64>::offset(10, 3), Ok(7));
+        assert_eq!(Generator::<u16>::offset(u16::MAX, 0), Ok(65535));
+        assert!(Generator::<u128>::offset(1 << 100, 0).is_err());
+        assert!(Generator::<u128>::offset(u128::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_scan() {
+        let mut gener = Generator::<u32>::new();
+        let mut gener_scan = Generator::<u32>::new();
+        for (n, aliquot_seq) in gener_scan.scan(1..276) {
+            assert_eq!(aliquot_seq, gener.aliquot_seq(n));
+        }
+        let scanned = gener_scan.scan(1264460..1264461).collect::<Vec<_>>();
+        assert_eq!(scanned, vec![(1264460, gener.aliquot_seq(1264460))]);
+        // The stats are updated as by aliquot_seq
+        let (handle, handle_scan) = (StatsHandle::new(), StatsHandle::new());
+        let mut gener = Generator::<u32>::new();
+        let mut gener_scan = Generator::<u32>::new();
+        gener.set_stats_handle(Some(handle.clone()));
+        gener_scan.set_stats_handle(Some(handle_scan.clone()));
+        for n in 1..300 {
+            gener.aliquot_seq(n);
+        }
+        assert_eq!(gener_scan.scan(1..300).count(), 299);
+        assert_eq!(handle_scan.snapshot(), handle.snapshot());
+        assert_eq!(handle_scan.snapshot().total(), 299);
+        // Only the sums exceeding the maximum of the type are computed by trial division
+        let mut gener = Generator::<u16>::new();
+        let scanned = gener.scan(65519..65521).collect::<Vec<_>>();
+        assert_eq!(scanned[0].1, Generator::<u16>::new().aliquot_seq(65519));
+        assert!(scanned[1].1.unknown_reason().is_some());
+    }
+
+    #[test]
+    fn test_aliquot_seq_map() {
+        let mut reference = Generator::<u32>::with_params(1_000_000, 1000, 0, false);
+        let mut gener = Generator::<u32>::with_params(1_000_000, 1000, 100_000, false);
+        let map = gener.aliquot_seq_map(1..300);
+        assert_eq!(map.len(), 299);
+        for n in 1..300 {
+            assert_eq!(map[&n], reference.aliquot_seq(n));
+        }
+        assert_eq!(map[&220], AliquotSeq::AmicableNumber((220, 284)));
+        assert!(!map.contains_key(&300));
+        // The sequences have been cached during the computation
+        assert!(gener.cache().n_seq() > 0);
+        assert_eq!(gener.cache().get(284), Some(map[&284].clone()));
+        assert!(gener.aliquot_seq_map(5..5).is_empty());
+    }
+
+    #[test]
+    fn test_aliquot_seqs() {
+        let mut gener = Generator::<u32>::new();
+        let ns = [1264460, 284, 12, 95, 220, 12, 7, 1, 562];
+        let aliquot_seqs = Generator::<u32>::new().aliquot_seqs(&ns);
+        assert_eq!(aliquot_seqs.len(), ns.len());
+        for (n, aliquot_seq) in ns.iter().zip(&aliquot_seqs) {
+            assert_eq!(aliquot_seq.number(), *n);
+            assert_eq!(*aliquot_seq, gener.aliquot_seq(*n));
+        }
+        assert!(gener.aliquot_seqs(&[]).is_empty());
     }
 }
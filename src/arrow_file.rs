@@ -0,0 +1,114 @@
+use crate::aliquot::{AliquotSeq, Generator};
+use crate::error::AliquotError;
+use crate::types::{Number, iter_range};
+use arrow::array::{ArrayRef, StringBuilder, UInt64Builder};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::RecordBatch;
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+impl<T: Number> Generator<T> {
+    /// Computes the aliquot sequences of all numbers in the range and writes
+    /// them to an Arrow IPC file, which can be read by pandas or polars. The
+    /// file contains the columns n, type, length, max_term and terminal, where
+    /// terminal is null for cycles and open sequences. All numbers are stored
+    /// as u64, so terms exceeding its maximum result in a conversion error.
+    pub fn write_arrow(&mut self, range: Range<T>, path: &Path) -> Result<(), AliquotError> {
+        let to_u64 = |t: T| -> Result<u64, AliquotError> {
+            t.try_into().map_err(|_| {
+                let err_msg = format!("{t} exceeds the maximum {}", u64::MAX);
+                AliquotError::conversion(err_msg)
+            })
+        };
+        let mut n_col = UInt64Builder::new();
+        let mut type_col = StringBuilder::new();
+        let mut len_col = UInt64Builder::new();
+        let mut max_col = UInt64Builder::new();
+        let mut terminal_col = UInt64Builder::new();
+        for n in iter_range(range) {
+            n_col.append_value(to_u64(n)?);
+            let aliquot_seq = self.aliquot_seq(n);
+            let max_term = aliquot_seq
+                .seq()
+                .into_iter()
+                .fold(T::ZERO, |max, t| if t > max { t } else { max });
+            // The reason of unknown sequences is left out, so types can be grouped
+            match aliquot_seq {
+                AliquotSeq::Unknown(_, _) => type_col.append_value("Unknown sequence"),
+                _ => type_col.append_value(aliquot_seq.type_str()),
+            }
+            len_col.append_value(aliquot_seq.len() as u64);
+            max_col.append_value(to_u64(max_term)?);
+            terminal_col.append_option(aliquot_seq.terminal().map(to_u64).transpose()?);
+        }
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("n", DataType::UInt64, false),
+            Field::new("type", DataType::Utf8, false),
+            Field::new("length", DataType::UInt64, false),
+            Field::new("max_term", DataType::UInt64, false),
+            Field::new("terminal", DataType::UInt64, true),
+        ]));
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(n_col.finish()),
+            Arc::new(type_col.finish()),
+            Arc::new(len_col.finish()),
+            Arc::new(max_col.finish()),
+            Arc::new(terminal_col.finish()),
+        ];
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+        let mut writer = FileWriter::try_new(File::create(path)?, &schema)?;
+        writer.write(&batch)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::UInt64Type;
+    use arrow::ipc::reader::FileReader;
+
+    #[test]
+    fn test_write_arrow() {
+        let path = std::env::temp_dir().join(format!("aliquot-seqs-{}.arrow", std::process::id()));
+        let mut gener = Generator::<u32>::new();
+        gener.write_arrow(1..301, &path).unwrap();
+        let reader = FileReader::try_new(File::open(&path).unwrap(), None).unwrap();
+        let batches = reader.map(|b| b.unwrap()).collect::<Vec<RecordBatch>>();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 300);
+        let batch = &batches[0];
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        let n = column("n");
+        let n = n.as_primitive::<UInt64Type>();
+        let types = column("type");
+        let types = types.as_string::<i32>();
+        let lengths = column("length");
+        let lengths = lengths.as_primitive::<UInt64Type>();
+        let max_terms = column("max_term");
+        let max_terms = max_terms.as_primitive::<UInt64Type>();
+        let terminals = column("terminal");
+        let terminals = terminals.as_primitive::<UInt64Type>();
+        // Row of 12: [12, 16, 15, 9, 4, 3, 1]
+        assert_eq!(n.value(11), 12);
+        assert_eq!(types.value(11), "Convergent sequence");
+        assert_eq!((lengths.value(11), max_terms.value(11)), (7, 16));
+        assert_eq!(terminals.value(11), 1);
+        // Row of 220, which is amicable and therefore has no terminal
+        assert_eq!(types.value(219), "Amicable number");
+        assert_eq!(max_terms.value(219), 284);
+        assert!(terminals.is_null(219));
+        // Row of 276, which exceeds the maximum of u32
+        assert_eq!(types.value(275), "Unknown sequence");
+        std::fs::remove_file(&path).unwrap();
+        // Numbers beyond u64 can't be written
+        let mut gener = Generator::<u128>::new();
+        let n = 1u128 << 64;
+        assert!(gener.write_arrow(n..n + 1, &path).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,58 @@
+use crate::types::Number;
+use std::ops::Range;
+
+/// Set of numbers within a range stored as bits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BitSet<T: Number> {
+    range: Range<T>,
+    bits: Vec<u64>,
+}
+
+impl<T: Number> BitSet<T> {
+    /// Returns a new empty set for numbers within the range.
+    pub fn new(range: Range<T>) -> Self {
+        let len = Self::offset(&range, range.end).unwrap_or(0);
+        Self {
+            range,
+            bits: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    /// Returns the offset of n from the start of the range.
+    fn offset(range: &Range<T>, n: T) -> Option<usize> {
+        if n < range.start {
+            return None;
+        }
+        (n - range.start)
+            .try_into()
+            .ok()
+            .map(|ind: u64| ind as usize)
+    }
+
+    /// Returns the range covered by the set.
+    pub fn range(&self) -> &Range<T> {
+        &self.range
+    }
+
+    /// Adds n to the set. Numbers outside of the range are ignored.
+    pub fn insert(&mut self, n: T) {
+        if n < self.range.end
+            && let Some(ind) = Self::offset(&self.range, n)
+        {
+            self.bits[ind / 64] |= 1 << (ind % 64);
+        }
+    }
+
+    /// Returns true, if n is contained in the set.
+    pub fn contains(&self, n: T) -> bool {
+        match Self::offset(&self.range, n) {
+            Some(ind) if n < self.range.end => (self.bits[ind / 64] >> (ind % 64)) & 1 == 1,
+            _ => false,
+        }
+    }
+
+    /// Returns the number of numbers contained in the set.
+    pub fn count(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
+    }
+}
@@ -1,16 +1,71 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter, Result};
-use std::num::ParseIntError;
+use std::io;
+use std::num::{ParseIntError, TryFromIntError};
+use std::sync::Arc;
 
-#[derive(Clone, Debug, PartialEq)]
+/// The underlying error, which caused an AliquotError. It is shared, so the
+/// AliquotError can still be cloned.
+pub type ErrorSource = Arc<dyn Error + Send + Sync>;
+
+#[derive(Clone, Debug)]
 pub enum AliquotError {
     InvalidArg(String),
     InvalidRange(String),
-    ConversionError(String),
+    ConversionError {
+        msg: String,
+        source: Option<ErrorSource>,
+    },
     OverflowError(String),
+    IoError {
+        msg: String,
+        source: Option<ErrorSource>,
+    },
+}
+
+impl AliquotError {
+    /// Returns a conversion error without a source.
+    pub fn conversion(msg: impl Into<String>) -> AliquotError {
+        AliquotError::ConversionError {
+            msg: msg.into(),
+            source: None,
+        }
+    }
 }
 
-impl Error for AliquotError {}
+impl Error for AliquotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AliquotError::ConversionError {
+                source: Some(source),
+                ..
+            }
+            | AliquotError::IoError {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Errors are equal, if their variants and messages are equal. The sources
+/// are not compared.
+impl PartialEq for AliquotError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (AliquotError::InvalidArg(a), AliquotError::InvalidArg(b)) => a == b,
+            (AliquotError::InvalidRange(a), AliquotError::InvalidRange(b)) => a == b,
+            (
+                AliquotError::ConversionError { msg: a, .. },
+                AliquotError::ConversionError { msg: b, .. },
+            ) => a == b,
+            (AliquotError::OverflowError(a), AliquotError::OverflowError(b)) => a == b,
+            (AliquotError::IoError { msg: a, .. }, AliquotError::IoError { msg: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
 
 impl Display for AliquotError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
@@ -21,18 +76,104 @@ impl Display for AliquotError {
             AliquotError::InvalidRange(msg) => {
                 write!(f, "Invalid range: {msg}")
             }
-            AliquotError::ConversionError(msg) => {
+            AliquotError::ConversionError { msg, .. } => {
                 write!(f, "Conversion error: {msg}")
             }
             AliquotError::OverflowError(msg) => {
                 write!(f, "Overflow error: {msg}")
             }
+            AliquotError::IoError { msg, .. } => {
+                write!(f, "IO error: {msg}")
+            }
         }
     }
 }
 
 impl From<ParseIntError> for AliquotError {
     fn from(error: ParseIntError) -> AliquotError {
-        AliquotError::ConversionError(error.to_string())
+        AliquotError::ConversionError {
+            msg: error.to_string(),
+            source: Some(Arc::new(error)),
+        }
+    }
+}
+
+impl From<TryFromIntError> for AliquotError {
+    fn from(error: TryFromIntError) -> AliquotError {
+        AliquotError::ConversionError {
+            msg: error.to_string(),
+            source: Some(Arc::new(error)),
+        }
+    }
+}
+
+impl From<io::Error> for AliquotError {
+    fn from(error: io::Error) -> AliquotError {
+        AliquotError::IoError {
+            msg: error.to_string(),
+            source: Some(Arc::new(error)),
+        }
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl From<arrow::error::ArrowError> for AliquotError {
+    fn from(error: arrow::error::ArrowError) -> AliquotError {
+        AliquotError::IoError {
+            msg: error.to_string(),
+            source: Some(Arc::new(error)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for AliquotError {
+    fn from(error: serde_json::Error) -> AliquotError {
+        AliquotError::ConversionError {
+            msg: error.to_string(),
+            source: Some(Arc::new(error)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_error_source() {
+        let err = AliquotError::from(u32::from_str("12a").unwrap_err());
+        assert_eq!(
+            err.to_string(),
+            "Conversion error: invalid digit found in string"
+        );
+        let source = err.source().unwrap();
+        assert_eq!(
+            source.downcast_ref::<ParseIntError>(),
+            Some(&u32::from_str("12a").unwrap_err())
+        );
+        let err = AliquotError::from(io::Error::other("disk full"));
+        assert_eq!(err.source().unwrap().to_string(), "disk full");
+        assert!(
+            AliquotError::OverflowError("1 plus 2".to_string())
+                .source()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_from_try_from_int_error() {
+        let err = AliquotError::from(u16::try_from(70000u32).unwrap_err());
+        assert_eq!(
+            err,
+            AliquotError::conversion("out of range integral type conversion attempted")
+        );
+        assert!(err.source().unwrap().is::<TryFromIntError>());
+        assert!(
+            AliquotError::conversion("Empty sequence")
+                .source()
+                .is_none()
+        );
     }
 }
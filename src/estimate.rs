@@ -0,0 +1,76 @@
+use crate::aliquot::Generator;
+use crate::error::AliquotError;
+use crate::types::Number;
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+/// Rough estimate of the work needed to compute the aliquot sums for a range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkEstimate {
+    /// Total number of numbers contained in the range.
+    pub count: u64,
+    /// Number of numbers actually computed to obtain the estimate.
+    pub n_samples: u64,
+    /// Average time needed to compute a single aliquot sum.
+    pub avg_cost: Duration,
+}
+
+impl WorkEstimate {
+    /// Returns the projected runtime for the whole range.
+    pub fn total(&self) -> Duration {
+        self.avg_cost.mul_f64(self.count as f64)
+    }
+}
+
+/// Estimates the work for a range by computing the aliquot sums of at most
+/// sample_size numbers evenly spread across the range. Since only the first
+/// step of each sequence is measured, the estimate is a lower bound at best.
+pub fn estimate_work<T: Number>(
+    range: Range<T>,
+    sample_size: usize,
+) -> Result<WorkEstimate, AliquotError> {
+    let count = if range.end > range.start {
+        (range.end - range.start).try_into().unwrap_or(u64::MAX)
+    } else {
+        0
+    };
+    let n_samples = count.min(sample_size as u64);
+    if n_samples == 0 {
+        return Ok(WorkEstimate {
+            count,
+            n_samples,
+            avg_cost: Duration::ZERO,
+        });
+    }
+    let step = count / n_samples;
+    let start = Instant::now();
+    for i in 0..n_samples {
+        let offset = T::try_from(i * step)
+            .map_err(|_| AliquotError::conversion(format!("Offset {} out of range", i * step)))?;
+        Generator::<T>::aliquot_sum(range.start + offset)?;
+    }
+    let avg_cost = start.elapsed() / n_samples as u32;
+    Ok(WorkEstimate {
+        count,
+        n_samples,
+        avg_cost,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_work() {
+        let estimate = estimate_work::<u64>(1..1_000_001, 100).unwrap();
+        assert_eq!(estimate.count, 1_000_000);
+        assert_eq!(estimate.n_samples, 100);
+        let estimate = estimate_work::<u16>(100..110, 100).unwrap();
+        assert_eq!(estimate.count, 10);
+        assert_eq!(estimate.n_samples, 10);
+        let estimate = estimate_work::<u32>(5..5, 100).unwrap();
+        assert_eq!(estimate.count, 0);
+        assert_eq!(estimate.total(), Duration::ZERO);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::aliquot::AliquotSeq;
+use crate::error::AliquotError;
+use crate::types::Number;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+/// Version of the JSON representation of aliquot sequences. It has to be
+/// increased, whenever the representation of AliquotSeq changes.
+pub const JSON_VERSION: u32 = 2;
+
+/// Serialized data wrapped together with the version of its representation.
+#[derive(Serialize, Deserialize)]
+struct Envelope<D> {
+    version: u32,
+    data: D,
+}
+
+/// Returns the data as JSON wrapped in a versioned envelope.
+fn to_envelope<D: Serialize>(data: &D) -> Result<String, AliquotError> {
+    let envelope = Envelope {
+        version: JSON_VERSION,
+        data,
+    };
+    Ok(serde_json::to_string(&envelope)?)
+}
+
+/// Returns the data from a versioned envelope. Envelopes of other versions
+/// are rejected.
+fn from_envelope<D: DeserializeOwned>(json: &str) -> Result<D, AliquotError> {
+    let envelope: Envelope<Value> = serde_json::from_str(json)?;
+    if envelope.version != JSON_VERSION {
+        let err_msg = format!(
+            "Unsupported JSON version {}, expected version {JSON_VERSION}",
+            envelope.version
+        );
+        return Err(AliquotError::conversion(err_msg));
+    }
+    Ok(serde_json::from_value(envelope.data)?)
+}
+
+impl<T: Number + Serialize + DeserializeOwned> AliquotSeq<T> {
+    /// Returns the aliquot sequence as JSON of the form {"version":2,"data":...}.
+    pub fn to_json(&self) -> Result<String, AliquotError> {
+        to_envelope(self)
+    }
+
+    /// Returns the aliquot sequence from JSON written by to_json.
+    pub fn from_json(json: &str) -> Result<Self, AliquotError> {
+        from_envelope(json)
+    }
+}
+
+/// Writes the aliquot sequences as JSON to a file.
+pub fn save<T: Number + Serialize>(
+    seqs: &[AliquotSeq<T>],
+    path: &Path,
+) -> Result<(), AliquotError> {
+    fs::write(path, to_envelope(&seqs)?)?;
+    Ok(())
+}
+
+/// Reads aliquot sequences from a file written by save.
+pub fn load<T: Number + DeserializeOwned>(path: &Path) -> Result<Vec<AliquotSeq<T>>, AliquotError> {
+    from_envelope(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aliquot::{UnknownMessage, UnknownReason};
+
+    #[test]
+    fn test_json_roundtrip() {
+        let seqs = vec![
+            AliquotSeq::<u64>::AmicableNumber((220, 284)),
+            AliquotSeq::Convergent(vec![12, 16, 15, 9, 4, 3, 1]),
+            AliquotSeq::Unknown(
+                vec![276, 396],
+                UnknownMessage::new(UnknownReason::MaxValue, "Exceeded"),
+            ),
+        ];
+        let json = seqs[0].to_json().unwrap();
+        assert_eq!(json, r#"{"version":2,"data":{"AmicableNumber":[220,284]}}"#);
+        assert_eq!(AliquotSeq::from_json(&json), Ok(seqs[0].clone()));
+        let path = std::env::temp_dir().join(format!("aliquot-seqs-{}.json", std::process::id()));
+        save(&seqs, &path).unwrap();
+        assert_eq!(load::<u64>(&path), Ok(seqs));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_json_version_mismatch() {
+        let json = r#"{"version":1,"data":{"AmicableNumber":[220,284]}}"#;
+        assert_eq!(
+            AliquotSeq::<u64>::from_json(json),
+            Err(AliquotError::conversion(
+                "Unsupported JSON version 1, expected version 2"
+            ))
+        );
+        let json = r#"{"data":{"PerfectNumber":6}}"#;
+        assert!(AliquotSeq::<u64>::from_json(json).is_err());
+    }
+}
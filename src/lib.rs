@@ -1,3 +1,16 @@
 pub mod aliquot;
+#[cfg(feature = "arrow")]
+pub mod arrow_file;
+pub mod bitset;
 pub mod error;
+pub mod estimate;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod output;
+pub mod repl;
+pub mod sample;
+pub mod sieve;
+pub mod stats;
 pub mod types;
@@ -1,31 +1,402 @@
 pub mod aliquot;
+#[cfg(feature = "arrow")]
+pub mod arrow_file;
+pub mod bitset;
 pub mod error;
+pub mod estimate;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod output;
+pub mod repl;
+pub mod sample;
+pub mod sieve;
+pub mod stats;
 pub mod types;
 
 use crate::aliquot::*;
 use crate::error::AliquotError;
+use crate::estimate::estimate_work;
+use crate::output::{BASES, format_seq, format_term};
+use crate::repl::Repl;
+use crate::sample::{sample_range, shuffled};
+use crate::stats::{Stats, StatsHandle};
+use crate::types::{Number, iter_range_inclusive};
 use std::env;
-use std::ops::Range;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::num::ParseIntError;
+use std::ops::{Range, RangeInclusive};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 fn help() {
     println!("Usage: aliquot [-m] NUMBER(s)");
-    println!("-n MAX      Maximum number of numbers in a sequence (default: 1000000)");
+    println!("-n MAX              Maximum number of numbers in a sequence (default: 1000000)");
     println!(
-        "-m MAX      Maximum value for a number in a sequence (default: {})",
-        u64::MAX
+        "-m MAX              Maximum value for a number in a sequence (default: maximum of TYPE, env: ALIQUOT_MAX_NUM)"
     );
-    println!("-c SIZE     Cache size (default: 1000000)");
-    println!("-l          Just print the lengths of the sequences");
-    println!("-t THREADS  Set the number of threads to use");
-    println!("-s          Just compute the aliquot sum instead of the aliquot sequence");
-    println!("-v          Print debug messages");
-    println!("-h          Print this help");
+    println!("--type TYPE         Type of the numbers: u16, u32, u64 or u128 (default: u64)");
+    println!("-c SIZE             Cache size (default: 1000000, env: ALIQUOT_CACHE_SIZE)");
+    println!(
+        "--cache-policy P    Policy for a full cache: reject-new, lru or keep-longest (default: reject-new)"
+    );
+    println!("--max-mem BYTES     Maximum memory used by the caches of all threads");
+    println!("--seed-cache N      Add all primes and perfect numbers up to N to the cache");
+    println!("-l                  Just print the lengths of the sequences");
+    println!("--len-min N         Only print sequences with at least N numbers");
+    println!("--len-max M         Only print sequences with at most M numbers");
+    println!(
+        "-t THREADS          Set the number of threads to use (default: 1, env: ALIQUOT_THREADS)"
+    );
+    println!("--max-time SECONDS  Stop computing after SECONDS");
+    println!("--warn-truncated    Print to stderr, which limit cut off an unknown sequence");
+    println!("-s                  Just compute the aliquot sum instead of the aliquot sequence");
+    println!("--both              Print the aliquot sum together with the aliquot sequence");
+    println!("--dump-cycles       Print all amicable and sociable cycles found at the end");
+    println!("--summary           Print the count of each type of sequence at the end");
+    println!("--progress          Print the number of computed sequences to stderr every second");
+    println!("--group-by-fate     Print the numbers grouped by the fate of their sequences");
+    println!("--compare A B       Compare the types of sequences of two lists of numbers");
+    println!("--sample K          Compute the sequences of K random numbers of each range");
+    println!("--seed S            Seed for choosing the random numbers (default: 0)");
+    println!("--shuffle           Compute the numbers of each range in a fixed shuffled order");
+    println!("--estimate          Just print an estimate of the work for the given numbers");
+    println!("--bench             Don't print the results, but the throughput to stderr");
+    println!("--base BASE         Print the numbers in base 2, 8, 10 or 16 (default: 10)");
+    println!("-o FILE             Write the sequences to FILE instead of stdout");
+    println!("--append            Append to the file given by -o instead of overwriting it");
+    println!("--repl              Read commands like \"seq 12\" from stdin");
+    println!("-v                  Print debug messages");
+    println!("-h                  Print this help");
 }
 
-fn run() -> Result<(), AliquotError> {
-    let args = env::args().collect::<Vec<String>>();
+/// Returns the numbers of the inclusive range as a Range. The maximum of T
+/// can't be contained in a Range, so it is returned separately.
+fn split_max<T: Number>(range: RangeInclusive<T>) -> (Range<T>, Option<T>) {
+    let (start, end) = range.into_inner();
+    if end < T::MAX {
+        (start..end + T::ONE, None)
+    } else {
+        (start..end, Some(end))
+    }
+}
+
+/// Parses a list of comma-separated numbers or ranges like "1-100,200". The
+/// ranges include their last number, so they may end at the maximum of T.
+fn parse_ranges<T: Number + FromStr<Err = ParseIntError>>(
+    arg: &str,
+) -> Result<Vec<RangeInclusive<T>>, AliquotError> {
+    let mut ranges = vec![];
+    for splt in arg.split(',') {
+        let range = match splt.find('-') {
+            Some(pos) => {
+                let (start_str, end_str) = splt.split_at(pos);
+                let start = T::from_str(start_str)?;
+                let end = T::from_str(&end_str[1..])?;
+                if end < start {
+                    let err_msg = format!("{start} - {end}");
+                    return Err(AliquotError::InvalidRange(err_msg));
+                }
+                start..=end
+            }
+            None => {
+                // This is just a single number
+                let num = T::from_str(splt)?;
+                num..=num
+            }
+        };
+        ranges.push(range);
+    }
+    Ok(ranges)
+}
+
+/// Returns the aliquot sum followed by the type and the terms of the sequence.
+/// The aliquot sum is the second term of the sequence, so it only needs to be
+/// computed again, if the sequence was aborted after the first term.
+fn sum_and_seq_string<T: Number>(
+    aliquot_seq: &AliquotSeq<T>,
+    base: u32,
+) -> Result<String, AliquotError> {
+    let n = aliquot_seq.number();
+    let sum = match aliquot_seq {
+        AliquotSeq::PerfectNumber(p) => *p,
+        _ => match aliquot_seq.seq().get(1) {
+            Some(&sum) => sum,
+            None => Generator::<T>::aliquot_sum(n)?,
+        },
+    };
+    Ok(format!(
+        "{}: sum={} {} {}",
+        format_term(n, base),
+        format_term(sum, base),
+        aliquot_seq.type_str(),
+        format_seq(aliquot_seq, base)
+    ))
+}
+
+/// Calls f for all numbers until the stop flag is set or f returns false for
+/// a number it could not complete. Returns the first number not computed or
+/// None, if all numbers have been computed.
+fn scan_until<T: Number>(
+    numbers: impl Iterator<Item = T>,
+    stop: &AtomicBool,
+    mut f: impl FnMut(T) -> Result<bool, AliquotError>,
+) -> Result<Option<T>, AliquotError> {
+    for n in numbers {
+        if stop.load(Ordering::Relaxed) || !f(n)? {
+            return Ok(Some(n));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns a warning naming the limit, which cut off an unknown sequence, or
+/// None, if the sequence has not been cut off.
+fn truncation_warning<T: Number>(aliquot_seq: &AliquotSeq<T>) -> Option<String> {
+    let cause = match aliquot_seq.unknown_reason()? {
+        UnknownReason::MaxLength => "the maximum length (-n)",
+        UnknownReason::MaxValue => "the maximum value (-m)",
+        UnknownReason::Arithmetic => "an arithmetic error",
+        UnknownReason::Interrupted => "the time limit (--max-time)",
+        UnknownReason::Undefined => return None,
+    };
+    Some(format!(
+        "Warning: Sequence of {} cut off by {cause}",
+        aliquot_seq.number()
+    ))
+}
+
+/// Increments the counter, if the sequence has been cut off by the maximum value.
+fn count_escaped<T: Number>(aliquot_seq: &AliquotSeq<T>, escaped: &AtomicUsize) {
+    if aliquot_seq.unknown_reason() == Some(UnknownReason::MaxValue) {
+        escaped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns the number of computed numbers per second. Numbers computed in no
+/// measurable time result in an infinite throughput.
+fn throughput(count: usize, elapsed: Duration) -> f64 {
+    count as f64 / elapsed.as_secs_f64()
+}
+
+/// Returns the report of a benchmark run printed by --bench.
+fn bench_report(count: usize, elapsed: Duration) -> String {
+    format!(
+        "Numbers: {count}, elapsed: {:.3} s, throughput: {:.1} numbers/s",
+        elapsed.as_secs_f64(),
+        throughput(count, elapsed)
+    )
+}
+
+/// Returns true, if the length of the sequence is within len_min and len_max.
+fn has_len_in<T: Number>(aliquot_seq: &AliquotSeq<T>, len_min: usize, len_max: usize) -> bool {
+    (len_min..=len_max).contains(&aliquot_seq.len())
+}
+
+/// Returns a line for every fate listing the numbers, whose sequences share this fate.
+fn fate_groups<T: Number>(aliquot_seqs: &[AliquotSeq<T>]) -> Vec<String> {
+    let groups = [
+        (Fate::Terminates, "Terminate at 1"),
+        (Fate::Perfect, "Perfect"),
+        (Fate::Cycle, "Cycles"),
+        (Fate::Open, "Open"),
+    ];
+    groups
+        .iter()
+        .map(|&(fate, header)| {
+            let numbers = aliquot_seqs
+                .iter()
+                .filter(|s| s.fate() == fate)
+                .map(|s| s.number().to_string())
+                .collect::<Vec<String>>();
+            format!("{header}: {}", numbers.join(", "))
+        })
+        .collect()
+}
+
+/// Opens the output file. The file is truncated, unless append is set.
+fn open_output(path: &str, append: bool) -> Result<File, AliquotError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(path)?;
+    Ok(file)
+}
+
+/// Returns the size of the cache of each thread. A memory budget in bytes takes
+/// precedence over the cache size and is shared by all threads.
+fn cache_size_per_thread<T: Number>(
+    max_cache_size: usize,
+    max_mem: Option<usize>,
+    n_threads: usize,
+) -> usize {
+    match max_mem {
+        Some(max_mem) => Cache::<T>::capacity_for_bytes(max_mem / n_threads),
+        None => max_cache_size / n_threads,
+    }
+}
+
+/// Prints a table comparing the counts of each type of sequence.
+fn print_compare(stats0: &Stats, stats1: &Stats) {
+    println!(
+        "{:<24}{:>12}{:>12}{:>12}",
+        "Type", "First", "Second", "Delta"
+    );
+    let counts0 = stats0.counts();
+    let counts1 = stats1.counts();
+    let diff = stats0.diff(stats1);
+    for i in 0..diff.len() {
+        let (name, delta) = diff[i];
+        println!(
+            "{:<24}{:>12}{:>12}{:>+12}",
+            name, counts0[i].1, counts1[i].1, delta
+        );
+    }
+    println!(
+        "{:<24}{:>12}{:>12}{:>+12}",
+        "Total",
+        stats0.total(),
+        stats1.total(),
+        stats1.total() as i64 - stats0.total() as i64
+    );
+}
+
+/// Logger printing all records up to debug level to stdout.
+#[cfg(feature = "log")]
+struct DebugLogger;
+
+#[cfg(feature = "log")]
+impl log::Log for DebugLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Debug
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            println!("{}: {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Options of the command line interface. All numbers are parsed into the
+/// type selected by --type.
+struct Options<T: Number> {
+    debug: bool,
+    max_len_seq: usize,
+    max_num: T,
+    max_cache_size: usize,
+    max_mem: Option<usize>,
+    cache_policy: CachePolicy,
+    seed_cache: T,
+    lengths_only: bool,
+    len_min: usize,
+    len_max: usize,
+    aliquot_sum_only: bool,
+    both: bool,
+    group_by_fate: bool,
+    dump_cycles: bool,
+    summary: bool,
+    progress: bool,
+    estimate: bool,
+    bench: bool,
+    sample: Option<usize>,
+    seed: u64,
+    shuffle: bool,
+    compare: Option<[Vec<RangeInclusive<T>>; 2]>,
+    n_threads: usize,
+    max_time: Option<Duration>,
+    warn_truncated: bool,
+    ranges: Vec<RangeInclusive<T>>,
+    base: u32,
+    output: Option<String>,
+    append: bool,
+    repl: bool,
+    help: bool,
+}
+
+impl<T: Number> Default for Options<T> {
+    fn default() -> Self {
+        Self {
+            debug: false,
+            max_len_seq: 1_000_000,
+            max_num: T::MAX,
+            max_cache_size: 1_000_000,
+            max_mem: None,
+            cache_policy: CachePolicy::RejectNew,
+            seed_cache: T::ZERO,
+            lengths_only: false,
+            len_min: 0,
+            len_max: usize::MAX,
+            aliquot_sum_only: false,
+            both: false,
+            group_by_fate: false,
+            dump_cycles: false,
+            summary: false,
+            progress: false,
+            estimate: false,
+            bench: false,
+            sample: None,
+            seed: 0,
+            shuffle: false,
+            compare: None,
+            n_threads: 1,
+            max_time: None,
+            warn_truncated: false,
+            ranges: vec![],
+            base: 10,
+            output: None,
+            append: false,
+            repl: false,
+            help: false,
+        }
+    }
+}
+
+/// Returns the parsed value of an environment variable or the default value,
+/// if the variable isn't set.
+fn env_or<T: FromStr<Err = ParseIntError>>(
+    env_var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    default: T,
+) -> Result<T, AliquotError> {
+    match env_var(name) {
+        Some(value) => T::from_str(&value).map_err(|err| AliquotError::ConversionError {
+            msg: format!("{name}={value}: {err}"),
+            source: Some(Arc::new(err)),
+        }),
+        None => Ok(default),
+    }
+}
+
+/// Returns the type of the numbers given by --type or u64 by default.
+fn parse_type(args: &[String]) -> Result<&str, AliquotError> {
+    match args.iter().position(|arg| arg == "--type") {
+        Some(ind) => match args.get(ind + 1) {
+            Some(num_type) => Ok(num_type),
+            None => {
+                let err_msg = format!("Missing argument at index {}", ind + 1);
+                Err(AliquotError::InvalidArg(err_msg))
+            }
+        },
+        None => Ok("u64"),
+    }
+}
+
+/// Parses the command line arguments. Some defaults are read from environment
+/// variables using env_var, but are overridden by the arguments.
+fn parse_args<T: Number + FromStr<Err = ParseIntError>>(
+    args: &[String],
+    env_var: impl Fn(&str) -> Option<String>,
+) -> Result<Options<T>, AliquotError> {
     let get_arg = |ind: usize| -> Result<&String, AliquotError> {
         if ind < args.len() {
             return Ok(&args[ind]);
@@ -33,14 +404,11 @@ fn run() -> Result<(), AliquotError> {
         let err_msg = format!("Missing argument at index {ind}");
         Err(AliquotError::InvalidArg(err_msg))
     };
-    let mut debug = false;
-    let mut max_len_seq = 1_000_000;
-    let mut max_num = u64::MAX;
-    let mut max_cache_size = 1_000_000;
-    let mut lengths_only = false;
-    let mut aliquot_sum_only = false;
-    let mut n_threads = 1;
-    let mut ranges: Vec<Range<u64>> = vec![];
+    let mut opts = Options::default();
+    // The environment is only consulted for switches not given on the command line
+    let mut max_num = None;
+    let mut max_cache_size = None;
+    let mut n_threads = None;
     let mut ind = 1;
     while ind < args.len() {
         let arg = args[ind].as_str();
@@ -48,76 +416,325 @@ fn run() -> Result<(), AliquotError> {
             "-n" => {
                 ind += 1;
                 let arg_string = get_arg(ind)?;
-                max_len_seq = usize::from_str(arg_string)?;
+                opts.max_len_seq = usize::from_str(arg_string)?;
             }
             "-m" => {
                 ind += 1;
                 let arg_string = get_arg(ind)?;
-                max_num = u64::from_str(arg_string)?;
+                max_num = Some(T::from_str(arg_string)?);
+            }
+            "--type" => {
+                // The type has already been chosen by parse_type
+                ind += 1;
             }
             "-c" => {
                 ind += 1;
                 let arg_string = get_arg(ind)?;
-                max_cache_size = usize::from_str(arg_string)?;
+                max_cache_size = Some(usize::from_str(arg_string)?);
+            }
+            "--max-mem" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                opts.max_mem = Some(usize::from_str(arg_string)?);
+            }
+            "--cache-policy" => {
+                ind += 1;
+                opts.cache_policy = CachePolicy::from_str(get_arg(ind)?)?;
+            }
+            "--seed-cache" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                opts.seed_cache = T::from_str(arg_string)?;
             }
             "-l" => {
-                lengths_only = true;
+                opts.lengths_only = true;
+            }
+            "--len-min" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                opts.len_min = usize::from_str(arg_string)?;
+            }
+            "--len-max" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                opts.len_max = usize::from_str(arg_string)?;
             }
             "-t" => {
                 ind += 1;
                 let arg_string = get_arg(ind)?;
-                n_threads = usize::from_str(arg_string)?;
+                n_threads = Some(usize::from_str(arg_string)?);
+            }
+            "--max-time" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                let seconds = f64::from_str(arg_string)
+                    .map_err(|err| AliquotError::conversion(format!("{arg_string}: {err}")))?;
+                let max_time = Duration::try_from_secs_f64(seconds)
+                    .map_err(|err| AliquotError::InvalidArg(format!("{arg_string}: {err}")))?;
+                opts.max_time = Some(max_time);
+            }
+            "--warn-truncated" => {
+                opts.warn_truncated = true;
             }
             "-s" => {
-                aliquot_sum_only = true;
+                opts.aliquot_sum_only = true;
+            }
+            "--both" => {
+                opts.both = true;
+            }
+            "--group-by-fate" => {
+                opts.group_by_fate = true;
+            }
+            "--dump-cycles" => {
+                opts.dump_cycles = true;
+            }
+            "--summary" => {
+                opts.summary = true;
+            }
+            "--progress" => {
+                opts.progress = true;
+            }
+            "--compare" => {
+                let ranges0 = parse_ranges::<T>(get_arg(ind + 1)?)?;
+                let ranges1 = parse_ranges::<T>(get_arg(ind + 2)?)?;
+                opts.compare = Some([ranges0, ranges1]);
+                ind += 2;
+            }
+            "--sample" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                opts.sample = Some(usize::from_str(arg_string)?);
+            }
+            "--seed" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                opts.seed = u64::from_str(arg_string)?;
+            }
+            "--shuffle" => {
+                opts.shuffle = true;
+            }
+            "--estimate" => {
+                opts.estimate = true;
+            }
+            "--bench" => {
+                opts.bench = true;
+            }
+            "--base" => {
+                ind += 1;
+                let arg_string = get_arg(ind)?;
+                opts.base = u32::from_str(arg_string)?;
+                if !BASES.contains(&opts.base) {
+                    let err_msg = format!("Unsupported base {}", opts.base);
+                    return Err(AliquotError::InvalidArg(err_msg));
+                }
+            }
+            "-o" => {
+                ind += 1;
+                opts.output = Some(get_arg(ind)?.to_string());
+            }
+            "--append" => {
+                opts.append = true;
+            }
+            "--repl" => {
+                opts.repl = true;
             }
             "-v" => {
-                debug = true;
+                opts.debug = true;
             }
             "-h" => {
-                help();
-                return Ok(());
+                opts.help = true;
+                return Ok(opts);
             }
             _ => {
                 // We assume these are the ranges of numbers to compute the aliquot sequences for
-                for splt in arg.split(',') {
-                    let range = match splt.find('-') {
-                        Some(pos) => {
-                            let (start_str, end_str) = splt.split_at(pos);
-                            let start = u64::from_str(start_str)?;
-                            let end = u64::from_str(&end_str[1..])? + 1;
-                            if end < start {
-                                let err_msg = format!("{start} - {end}");
-                                return Err(AliquotError::InvalidRange(err_msg));
-                            }
-                            start..end
-                        }
-                        None => {
-                            // This is just a single number
-                            let num = u64::from_str(splt)?;
-                            num..(num + 1)
-                        }
-                    };
-                    ranges.push(range);
-                }
+                opts.ranges.append(&mut parse_ranges::<T>(arg)?);
             }
         }
         ind += 1;
     }
+    opts.max_num = match max_num {
+        Some(max_num) => max_num,
+        None => env_or(&env_var, "ALIQUOT_MAX_NUM", opts.max_num)?,
+    };
+    opts.max_cache_size = match max_cache_size {
+        Some(max_cache_size) => max_cache_size,
+        None => env_or(&env_var, "ALIQUOT_CACHE_SIZE", opts.max_cache_size)?,
+    };
+    opts.n_threads = match n_threads {
+        Some(n_threads) => n_threads,
+        None => env_or(&env_var, "ALIQUOT_THREADS", opts.n_threads)?,
+    };
+    if opts.append && opts.output.is_none() {
+        return Err(AliquotError::InvalidArg(
+            "--append requires -o FILE".to_string(),
+        ));
+    }
+    // These modes only print to stdout
+    if opts.output.is_some() {
+        let modes = [
+            ("--compare", opts.compare.is_some()),
+            ("--group-by-fate", opts.group_by_fate),
+            ("--estimate", opts.estimate),
+            ("--repl", opts.repl),
+        ];
+        if let Some((mode, _)) = modes.iter().find(|(_, enabled)| *enabled) {
+            let err_msg = format!("-o can't be combined with {mode}");
+            return Err(AliquotError::InvalidArg(err_msg));
+        }
+    }
+    Ok(opts)
+}
+
+fn run() -> Result<(), AliquotError> {
+    let args = env::args().collect::<Vec<String>>();
+    match parse_type(&args)? {
+        "u16" => run_generic::<u16>(&args),
+        "u32" => run_generic::<u32>(&args),
+        "u64" => run_generic::<u64>(&args),
+        "u128" => run_generic::<u128>(&args),
+        num_type => {
+            let err_msg = format!("Unsupported type {num_type}");
+            Err(AliquotError::InvalidArg(err_msg))
+        }
+    }
+}
+
+/// Runs the command line interface computing the sequences using numbers of type T.
+fn run_generic<T>(args: &[String]) -> Result<(), AliquotError>
+where
+    T: Number + FromStr<Err = ParseIntError> + Send + Sync + 'static,
+{
+    let opts = parse_args::<T>(args, |name| env::var(name).ok())?;
+    if opts.help {
+        help();
+        return Ok(());
+    }
+    let Options {
+        debug,
+        max_len_seq,
+        max_num,
+        max_cache_size,
+        max_mem,
+        cache_policy,
+        seed_cache,
+        lengths_only,
+        len_min,
+        len_max,
+        aliquot_sum_only,
+        both,
+        group_by_fate,
+        dump_cycles,
+        summary,
+        progress,
+        estimate,
+        bench,
+        sample,
+        seed,
+        shuffle,
+        compare,
+        n_threads,
+        max_time,
+        warn_truncated,
+        ranges,
+        base,
+        output,
+        append,
+        repl,
+        help: _,
+    } = opts;
+    // The stats are shared by all threads, so they can be polled during the run
+    let stats = (summary || progress).then(StatsHandle::new);
+    // Returns a new generator, whose cache holds cache_size numbers
+    let stats_handle = stats.clone();
+    let generator = move |cache_size: usize| -> Generator<T> {
+        let mut gener = Generator::<T>::with_params(max_num, max_len_seq, cache_size, debug);
+        gener.cache_mut().set_policy(cache_policy);
+        gener.set_stats_handle(stats_handle.clone());
+        gener
+    };
+    if repl {
+        let gener = generator(max_cache_size);
+        return Repl::new(gener).run(io::stdin().lock(), io::stdout());
+    }
+    if let Some([ranges0, ranges1]) = compare {
+        let mut gener = generator(max_cache_size);
+        let mut stats = |ranges: Vec<RangeInclusive<T>>| -> Stats {
+            let mut stats = Stats::new();
+            for n in ranges.into_iter().flat_map(iter_range_inclusive) {
+                stats.add(&gener.aliquot_seq(n));
+            }
+            stats
+        };
+        let stats0 = stats(ranges0);
+        let stats1 = stats(ranges1);
+        print_compare(&stats0, &stats1);
+        return Ok(());
+    }
+    if group_by_fate {
+        let mut gener = generator(max_cache_size);
+        let aliquot_seqs = ranges
+            .into_iter()
+            .flat_map(iter_range_inclusive)
+            .map(|n| gener.aliquot_seq(n))
+            .collect::<Vec<AliquotSeq<T>>>();
+        for line in fate_groups(&aliquot_seqs) {
+            println!("{line}");
+        }
+        return Ok(());
+    }
+    if estimate {
+        let mut count = 0;
+        let mut total = Duration::ZERO;
+        for range in ranges {
+            let (range, max) = split_max(range);
+            let work = estimate_work(range, 1000)?;
+            // The maximum of T costs about as much as the other numbers
+            let n_max = u64::from(max.is_some());
+            count += work.count + n_max;
+            total += work.total() + work.avg_cost * n_max as u32;
+        }
+        println!("Numbers: {count}");
+        println!(
+            "Estimated runtime: {:.3} s ({:.3} s per thread)",
+            total.as_secs_f64(),
+            total.as_secs_f64() / n_threads as f64
+        );
+        return Ok(());
+    }
+    // Replace the ranges by single random numbers
+    let ranges = match sample {
+        Some(k) => ranges
+            .into_iter()
+            .flat_map(|range| {
+                // Offsets are drawn, so the range may end at the maximum of T
+                let (start, end) = range.into_inner();
+                let (offsets, _) = split_max(T::ZERO..=(end - start));
+                sample_range(offsets, k, seed)
+                    .into_iter()
+                    .map(move |offset| start + offset)
+            })
+            .map(|n| n..=n)
+            .collect(),
+        None => ranges,
+    };
     // Distribute work to independent threads
     let mut workload = vec![vec![]; n_threads];
     if ranges.len() == 1 && n_threads > 1 {
-        let n_per_thread = (ranges[0].end - ranges[0].start) / n_threads as u64;
-        // Split the range
-        for (i, w) in workload.iter_mut().enumerate().take(n_threads) {
-            let ind = i as u64;
-            let start = ranges[0].start + (ind * n_per_thread);
-            let end = if i == (n_threads - 1) {
-                ranges[0].end
-            } else {
-                ranges[0].start + ((ind + 1) * n_per_thread)
-            };
-            w.push(start..end);
+        // Split the range, so every thread gets about the same work
+        let (range, max) = split_max(ranges[0].clone());
+        let mut chunks = Generator::<T>::balanced_chunks(range, n_threads)
+            .into_iter()
+            .map(|chunk| chunk.start..=(chunk.end - T::ONE))
+            .collect::<Vec<RangeInclusive<T>>>();
+        // The maximum of T is added to the last chunk
+        if let Some(max) = max {
+            match chunks.pop() {
+                Some(last) => chunks.push(*last.start()..=max),
+                None => chunks.push(max..=max),
+            }
+        }
+        for (w, chunk) in workload.iter_mut().zip(chunks) {
+            w.push(chunk);
         }
     } else {
         // Distribute the ranges among the threads
@@ -126,38 +743,136 @@ fn run() -> Result<(), AliquotError> {
             workload[i % n_threads].push(ranges[i].clone());
         }
     }
+    #[cfg(feature = "log")]
+    if debug {
+        static LOGGER: DebugLogger = DebugLogger;
+        if log::set_logger(&LOGGER).is_ok() {
+            log::set_max_level(log::LevelFilter::Debug);
+        }
+    }
+    #[cfg(feature = "log")]
+    log::debug!("Number of threads: {n_threads}");
+    #[cfg(not(feature = "log"))]
     if debug {
         println!("Debug: Number of threads: {n_threads}");
     }
     // Start computing sequences
     let mut handles = vec![];
-    for w in workload {
-        let handle = thread::spawn(move || -> Result<(), AliquotError> {
-            let mut gener = Generator::<u64>::with_params(
-                max_num,
-                max_len_seq,
-                max_cache_size / n_threads,
-                debug,
-            );
-            for range in w {
+    // All threads share the output, which is stdout by default
+    let writer: Box<dyn Write + Send> = match output {
+        Some(path) => Box::new(BufWriter::new(open_output(&path, append)?)),
+        None => Box::new(io::stdout()),
+    };
+    let out = Arc::new(Mutex::new(writer));
+    // The stop flag is set once the time is up, which also interrupts the
+    // sequences currently computed
+    let stop = Arc::new(AtomicBool::new(false));
+    if let Some(max_time) = max_time {
+        let stop = stop.clone();
+        thread::spawn(move || {
+            thread::sleep(max_time);
+            stop.store(true, Ordering::Relaxed);
+        });
+    }
+    // Number of sequences exceeding the maximum value in all threads
+    let escaped = Arc::new(AtomicUsize::new(0));
+    // Number of numbers computed by all threads for --bench
+    let processed = Arc::new(AtomicUsize::new(0));
+    let start = Instant::now();
+    // The progress is printed until all threads are done
+    let done = Arc::new(AtomicBool::new(false));
+    if progress && let Some(stats) = stats.clone() {
+        let done = done.clone();
+        thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_secs(1));
+                eprintln!("Progress: {} sequences computed", stats.snapshot().total());
+            }
+        });
+    }
+    for (i, w) in workload.into_iter().enumerate() {
+        let out = out.clone();
+        let stop = stop.clone();
+        let escaped = escaped.clone();
+        let processed = processed.clone();
+        let generator = generator.clone();
+        let handle = thread::spawn(move || -> Result<Vec<AliquotSeq<T>>, AliquotError> {
+            let print = |line: String| -> Result<(), AliquotError> {
+                if bench {
+                    return Ok(());
+                }
+                let mut out = out.lock().unwrap();
+                writeln!(out, "{line}")?;
+                Ok(())
+            };
+            let mut gener = generator(cache_size_per_thread::<T>(
+                max_cache_size,
+                max_mem,
+                n_threads,
+            ));
+            if seed_cache > T::ZERO {
+                gener.cache_mut().seed(seed_cache);
+            }
+            // Shuffling exercises the cache with non-sequential access patterns
+            let numbers: Box<dyn Iterator<Item = T>> = if shuffle {
+                let shuffled_ranges = w
+                    .into_iter()
+                    .map(|range| {
+                        // The maximum of T follows the shuffled numbers of its range
+                        let (range, max) = split_max(range);
+                        Ok(shuffled(range)?.chain(max))
+                    })
+                    .collect::<Result<Vec<_>, AliquotError>>()?;
+                Box::new(shuffled_ranges.into_iter().flatten())
+            } else {
+                Box::new(w.into_iter().flat_map(iter_range_inclusive))
+            };
+            let stopped_at = scan_until(numbers, &stop, |n| {
                 if aliquot_sum_only {
-                    for n in range {
-                        let aliquot_sum = Generator::<u64>::aliquot_sum(n)?;
-                        println!("{n} {aliquot_sum}");
-                    }
+                    processed.fetch_add(1, Ordering::Relaxed);
+                    let aliquot_sum = Generator::<T>::aliquot_sum(n)?;
+                    print(format!(
+                        "{} {}",
+                        format_term(n, base),
+                        format_term(aliquot_sum, base)
+                    ))?;
+                    return Ok(true);
+                }
+                let aliquot_seq = gener.aliquot_seq_interruptible(n, &stop);
+                // Partial sequences are not printed, n is reported as the resume point
+                if aliquot_seq.unknown_reason() == Some(UnknownReason::Interrupted) {
+                    return Ok(false);
+                }
+                processed.fetch_add(1, Ordering::Relaxed);
+                count_escaped(&aliquot_seq, &escaped);
+                if warn_truncated && let Some(warning) = truncation_warning(&aliquot_seq) {
+                    eprintln!("{warning}");
+                }
+                if !has_len_in(&aliquot_seq, len_min, len_max) {
+                    return Ok(true);
+                }
+                if lengths_only {
+                    print(format!("{} {}", n, aliquot_seq.len()))?;
+                } else if both {
+                    print(sum_and_seq_string(&aliquot_seq, base)?)?;
                 } else {
-                    for n in range {
-                        let aliquot_seq = gener.aliquot_seq(n);
-                        if lengths_only {
-                            println!("{} {}", n, aliquot_seq.len());
-                        } else {
-                            let type_str = aliquot_seq.type_str();
-                            let seq_string = aliquot_seq.seq_string();
-                            println!("{n}: {type_str} {seq_string}");
-                        }
-                    }
+                    let n = format_term(n, base);
+                    let type_str = aliquot_seq.type_str();
+                    let seq_string = format_seq(&aliquot_seq, base);
+                    print(format!("{n}: {type_str} {seq_string}"))?;
                 }
+                Ok(true)
+            })?;
+            if let Some(n) = stopped_at {
+                eprintln!("Thread {i} reached the time limit before computing {n}");
             }
+            #[cfg(feature = "log")]
+            log::info!(
+                "Cache stored {} sequences and {} numbers",
+                gener.cache().n_seq(),
+                gener.cache().count()
+            );
+            #[cfg(not(feature = "log"))]
             if debug {
                 println!(
                     "Debug: Cache stored {} sequences and {} numbers",
@@ -165,14 +880,42 @@ fn run() -> Result<(), AliquotError> {
                     gener.cache().count()
                 );
             }
-            Ok(())
+            // Only cached cycles are found, so the cache must not be disabled
+            if dump_cycles {
+                Ok(gener.cache().cycles())
+            } else {
+                Ok(vec![])
+            }
         });
         handles.push(handle);
     }
     // Sync threads
+    let mut cycles = vec![];
     for h in handles.into_iter() {
-        h.join().unwrap()?;
+        cycles.append(&mut h.join().unwrap()?);
+    }
+    done.store(true, Ordering::Relaxed);
+    if bench {
+        let count = processed.load(Ordering::Relaxed);
+        eprintln!("{}", bench_report(count, start.elapsed()));
+    }
+    let mut out = out.lock().unwrap();
+    if dump_cycles {
+        writeln!(out, "Cycles:")?;
+        for aliquot_seq in Cache::merge_cycles(cycles) {
+            let type_str = aliquot_seq.type_str();
+            writeln!(out, "{type_str} {}", format_seq(&aliquot_seq, base))?;
+        }
     }
+    if summary && let Some(stats) = stats {
+        writeln!(out, "Summary:")?;
+        for (name, count) in stats.snapshot().counts() {
+            writeln!(out, "{name}: {count}")?;
+        }
+        let escaped = escaped.load(Ordering::Relaxed);
+        writeln!(out, "Exceeding the maximum value (-m): {escaped}")?;
+    }
+    out.flush()?;
     Ok(())
 }
 
@@ -181,3 +924,370 @@ fn main() {
         println!("{err}");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_env() {
+        let env_var = |name: &str| -> Option<String> {
+            match name {
+                "ALIQUOT_MAX_NUM" => Some("4000000000".to_string()),
+                "ALIQUOT_CACHE_SIZE" => Some("5000".to_string()),
+                "ALIQUOT_THREADS" => Some("4".to_string()),
+                _ => None,
+            }
+        };
+        let opts = parse_args::<u64>(&args("aliquot 1-100"), env_var).unwrap();
+        assert_eq!(opts.max_num, 4_000_000_000);
+        assert_eq!(opts.max_cache_size, 5000);
+        assert_eq!(opts.n_threads, 4);
+        assert_eq!(opts.ranges, vec![1..=100]);
+        let opts = parse_args::<u64>(&args("aliquot -m 1000 -c 0 -t 2 1-100"), env_var).unwrap();
+        assert_eq!(opts.max_num, 1000);
+        assert_eq!(opts.max_cache_size, 0);
+        assert_eq!(opts.n_threads, 2);
+        let opts = parse_args::<u64>(&args("aliquot 1-100"), |_| None).unwrap();
+        assert_eq!(opts.max_num, u64::MAX);
+        assert_eq!(opts.max_cache_size, 1_000_000);
+        assert_eq!(opts.n_threads, 1);
+        let invalid = |_: &str| Some("many".to_string());
+        assert!(parse_args::<u64>(&args("aliquot 1-100"), invalid).is_err());
+        assert!(parse_args::<u64>(&args("aliquot -m 1000 -c 10 1-100"), invalid).is_err());
+        // Invalid values are ignored, if the switches override them
+        let opts = parse_args::<u64>(&args("aliquot -m 1000 -c 10 -t 2 1-100"), invalid).unwrap();
+        assert_eq!(opts.max_num, 1000);
+        assert_eq!(opts.max_cache_size, 10);
+        assert_eq!(opts.n_threads, 2);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parse = |line: &str| parse_args::<u64>(&args(line), |_| None);
+        let opts = parse("aliquot --max-mem 1000 1-10").unwrap();
+        assert_eq!(opts.max_mem, Some(1000));
+        assert_eq!(opts.cache_policy, CachePolicy::RejectNew);
+        assert!(parse("aliquot --both 12").unwrap().both);
+        assert!(!parse("aliquot 12").unwrap().both);
+        let opts = parse("aliquot --sample 10 --seed 3 1-1000").unwrap();
+        assert_eq!(opts.sample, Some(10));
+        assert_eq!(opts.seed, 3);
+        assert!(!opts.shuffle);
+        let opts = parse("aliquot -o out.txt --append 1-10").unwrap();
+        assert_eq!(opts.output, Some("out.txt".to_string()));
+        assert!(opts.append);
+        // Switches, which would ignore the output file, are rejected
+        assert_eq!(
+            parse("aliquot --append 1-10").err().unwrap(),
+            AliquotError::InvalidArg("--append requires -o FILE".to_string())
+        );
+        assert_eq!(
+            parse("aliquot -o out.txt --compare 1-9 10-19")
+                .err()
+                .unwrap(),
+            AliquotError::InvalidArg("-o can't be combined with --compare".to_string())
+        );
+        assert_eq!(
+            parse("aliquot -o out.txt --group-by-fate 1-10")
+                .err()
+                .unwrap(),
+            AliquotError::InvalidArg("-o can't be combined with --group-by-fate".to_string())
+        );
+        assert!(parse("aliquot -o out.txt --estimate 1-10").is_err());
+        assert!(parse("aliquot -o out.txt --repl").is_err());
+        assert_eq!(parse("aliquot --base 16 12").unwrap().base, 16);
+        assert!(parse("aliquot --base 3 12").is_err());
+        let opts = parse("aliquot --len-min 7 --len-max 20 1-30").unwrap();
+        assert_eq!((opts.len_min, opts.len_max), (7, 20));
+        let opts = parse("aliquot --max-time 0.5 1-10").unwrap();
+        assert_eq!(opts.max_time, Some(Duration::from_millis(500)));
+        assert!(parse("aliquot --max-time -1 1-10").is_err());
+        assert!(
+            parse("aliquot --warn-truncated 1-10")
+                .unwrap()
+                .warn_truncated
+        );
+        assert!(parse("aliquot --dump-cycles 1-10").unwrap().dump_cycles);
+        let opts = parse("aliquot --cache-policy keep-longest 1-10").unwrap();
+        assert_eq!(opts.cache_policy, CachePolicy::KeepLongest);
+        assert!(parse("aliquot --cache-policy lfu 1-10").is_err());
+        assert!(parse("aliquot --shuffle 1-1000").unwrap().shuffle);
+        assert!(parse("aliquot --bench 1-10").unwrap().bench);
+    }
+
+    #[test]
+    fn test_parse_type() {
+        assert_eq!(parse_type(&args("aliquot 1-100")), Ok("u64"));
+        assert_eq!(parse_type(&args("aliquot --type u16 1-100")), Ok("u16"));
+        assert!(parse_type(&args("aliquot 1-100 --type")).is_err());
+        let opts16 = parse_args::<u16>(&args("aliquot --type u16 1-99"), |_| None).unwrap();
+        let opts128 = parse_args::<u128>(&args("aliquot --type u128 1-99"), |_| None).unwrap();
+        assert_eq!(opts16.max_num, u16::MAX);
+        assert_eq!(opts128.max_num, u128::MAX);
+        assert!(parse_args::<u16>(&args("aliquot --type u16 -m 70000 1-99"), |_| None).is_err());
+        assert!(parse_args::<u16>(&args("aliquot --type u16 65536"), |_| None).is_err());
+        let mut gener16 = Generator::<u16>::new();
+        let mut gener128 = Generator::<u128>::new();
+        let numbers16 = opts16.ranges.into_iter().flat_map(iter_range_inclusive);
+        let numbers128 = opts128.ranges.into_iter().flat_map(iter_range_inclusive);
+        for (n16, n128) in numbers16.zip(numbers128) {
+            assert_eq!(u128::from(n16), n128);
+            let seq16 = gener16.aliquot_seq(n16);
+            let seq128 = gener128.aliquot_seq(n128);
+            assert_eq!(seq16.type_str(), seq128.type_str());
+            assert_eq!(seq16.len(), seq128.len());
+        }
+    }
+
+    #[test]
+    fn test_sum_and_seq_string() {
+        let mut gener = Generator::<u64>::new();
+        assert_eq!(
+            sum_and_seq_string(&gener.aliquot_seq(12), 10),
+            Ok("12: sum=16 Convergent sequence [12, 16, 15, 9, 4, 3, 1]".to_string())
+        );
+        assert_eq!(
+            sum_and_seq_string(&gener.aliquot_seq(28), 10),
+            Ok("28: sum=28 Perfect number 28".to_string())
+        );
+        assert_eq!(
+            sum_and_seq_string(&gener.aliquot_seq(220), 10),
+            Ok("220: sum=284 Amicable number 220, 284".to_string())
+        );
+        assert_eq!(
+            sum_and_seq_string(&gener.aliquot_seq(220), 16),
+            Ok("dc: sum=11c Amicable number dc, 11c".to_string())
+        );
+        let mut gener = Generator::<u64>::with_params(15, 100, 0, false);
+        assert_eq!(
+            sum_and_seq_string(&gener.aliquot_seq(12), 10),
+            Ok("12: sum=16 Unknown sequence (Reason: Maximum value 15 exceeded) [12]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_scan_until() {
+        let mut count = 0;
+        let stop = AtomicBool::new(false);
+        let stopped_at = scan_until(1..u64::MAX, &stop, |n| {
+            Generator::<u64>::aliquot_sum(n)?;
+            count += 1;
+            if count == 100 {
+                stop.store(true, Ordering::Relaxed);
+            }
+            Ok(true)
+        });
+        assert_eq!(stopped_at, Ok(Some(101)));
+        // A tiny time budget stops a large range early
+        let stop = Arc::new(AtomicBool::new(false));
+        let timer_stop = stop.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            timer_stop.store(true, Ordering::Relaxed);
+        });
+        let stopped_at = scan_until(1..u64::MAX, &stop, |n| {
+            Generator::<u64>::aliquot_sum(n)?;
+            Ok(true)
+        });
+        assert!(stopped_at.unwrap().is_some());
+        let mut count = 0;
+        let stop = AtomicBool::new(false);
+        let stopped_at = scan_until(1u64..1000, &stop, |_| {
+            count += 1;
+            Ok(true)
+        });
+        assert_eq!((stopped_at, count), (Ok(None), 999));
+        // A number interrupted while computing is the resume point
+        let mut gener = Generator::<u64>::new();
+        let stop = AtomicBool::new(false);
+        let stopped_at = scan_until(1u64..1000, &stop, |n| {
+            if n == 276 {
+                stop.store(true, Ordering::Relaxed);
+            }
+            let aliquot_seq = gener.aliquot_seq_interruptible(n, &stop);
+            Ok(aliquot_seq.unknown_reason() != Some(UnknownReason::Interrupted))
+        });
+        assert_eq!(stopped_at, Ok(Some(276)));
+    }
+
+    #[test]
+    fn test_truncation_warning() {
+        let mut gener = Generator::<u64>::with_params(1_000_000, 5, 100, false);
+        assert_eq!(
+            truncation_warning(&gener.aliquot_seq(276)),
+            Some("Warning: Sequence of 276 cut off by the maximum length (-n)".to_string())
+        );
+        assert_eq!(truncation_warning(&gener.aliquot_seq(7)), None);
+        assert_eq!(truncation_warning(&gener.aliquot_seq(1)), None);
+        let mut gener = Generator::<u64>::with_params(1000, 100, 100, false);
+        assert_eq!(
+            truncation_warning(&gener.aliquot_seq(138)),
+            Some("Warning: Sequence of 138 cut off by the maximum value (-m)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_bench_report() {
+        assert_eq!(throughput(1000, Duration::from_millis(500)), 2000.0);
+        assert_eq!(throughput(3, Duration::from_secs(4)), 0.75);
+        assert_eq!(throughput(10, Duration::ZERO), f64::INFINITY);
+        assert_eq!(
+            bench_report(1500, Duration::from_millis(1250)),
+            "Numbers: 1500, elapsed: 1.250 s, throughput: 1200.0 numbers/s"
+        );
+    }
+
+    #[test]
+    fn test_count_escaped() {
+        let escaped = Arc::new(AtomicUsize::new(0));
+        let handles = [1..150, 150..300].map(|range| {
+            let escaped = escaped.clone();
+            thread::spawn(move || {
+                let mut gener = Generator::<u64>::with_params(1000, 1000, 1000, false);
+                for n in range {
+                    count_escaped(&gener.aliquot_seq(n), &escaped);
+                }
+            })
+        });
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        let mut gener = Generator::<u64>::with_params(1000, 1000, 0, false);
+        let expected = (1..300)
+            .filter(|&n| gener.aliquot_seq(n).unknown_reason() == Some(UnknownReason::MaxValue))
+            .count();
+        assert!(expected > 0);
+        assert_eq!(escaped.load(Ordering::Relaxed), expected);
+        // Sequences cut off by the maximum length are not counted
+        let escaped = AtomicUsize::new(0);
+        let mut gener = Generator::<u64>::with_params(1_000_000, 5, 100, false);
+        count_escaped(&gener.aliquot_seq(276), &escaped);
+        assert_eq!(escaped.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_merge_cycles() {
+        let mut gener0 = Generator::<u64>::new();
+        let mut gener1 = Generator::<u64>::new();
+        for n in [220, 1264460] {
+            gener0.aliquot_seq(n);
+        }
+        for n in [284, 1184] {
+            gener1.aliquot_seq(n);
+        }
+        let mut cycles = gener0.cache().cycles();
+        cycles.append(&mut gener1.cache().cycles());
+        assert_eq!(
+            Cache::merge_cycles(cycles),
+            vec![
+                AliquotSeq::AmicableNumber((220, 284)),
+                AliquotSeq::AmicableNumber((1184, 1210)),
+                AliquotSeq::SociableNumber(vec![1264460, 1547860, 1727636, 1305184]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_has_len_in() {
+        let mut gener = Generator::<u64>::new();
+        let mut filtered = |len_min, len_max| {
+            (1..31)
+                .filter(|&n| has_len_in(&gener.aliquot_seq(n), len_min, len_max))
+                .collect::<Vec<u64>>()
+        };
+        assert_eq!(filtered(7, usize::MAX), vec![12, 20, 26, 30]);
+        assert_eq!(filtered(7, 7), vec![12, 20, 26]);
+        assert_eq!(filtered(0, 1), vec![1, 6, 28]);
+    }
+
+    #[test]
+    fn test_fate_groups() {
+        let mut gener = Generator::<u64>::new();
+        let aliquot_seqs = (1..30)
+            .chain([220, 562])
+            .map(|n| gener.aliquot_seq(n))
+            .collect::<Vec<AliquotSeq<u64>>>();
+        let terminating = (2..30)
+            .filter(|n| ![6, 25, 28].contains(n))
+            .map(|n| n.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(
+            fate_groups(&aliquot_seqs),
+            vec![
+                format!("Terminate at 1: {}", terminating.join(", ")),
+                "Perfect: 6, 25, 28".to_string(),
+                "Cycles: 220, 562".to_string(),
+                "Open: 1".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open_output() {
+        let path = env::temp_dir().join(format!("aliquot-output-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        let write = |append: bool, line: &str| {
+            let mut file = open_output(path, append).unwrap();
+            writeln!(file, "{line}").unwrap();
+        };
+        write(false, "6: Perfect number 6");
+        write(true, "7: Prime number 7, 1");
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "6: Perfect number 6\n7: Prime number 7, 1\n"
+        );
+        write(false, "28: Perfect number 28");
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            "28: Perfect number 28\n"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_cache_size_per_thread() {
+        assert_eq!(cache_size_per_thread::<u64>(1_000_000, None, 4), 250_000);
+        assert_eq!(
+            cache_size_per_thread::<u64>(1_000_000, Some(128_000_000), 4),
+            1_000_000
+        );
+        assert_eq!(cache_size_per_thread::<u32>(0, Some(1_600), 5), 20);
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(
+            parse_ranges::<u64>("1-100,276,300-310").unwrap(),
+            vec![1..=100, 276..=276, 300..=310]
+        );
+        assert_eq!(
+            parse_ranges::<u16>("65530-65535").unwrap(),
+            vec![65530..=u16::MAX]
+        );
+        assert_eq!(
+            parse_ranges::<u16>("65535").unwrap(),
+            vec![u16::MAX..=u16::MAX]
+        );
+        assert!(parse_ranges::<u16>("65536").is_err());
+        assert!(parse_ranges::<u16>("10-3").is_err());
+        let mut gener = Generator::<u16>::new();
+        let mut count = 0;
+        for range in parse_ranges::<u16>("65500-65535,65535").unwrap() {
+            for n in iter_range_inclusive(range) {
+                gener.aliquot_seq(n);
+                count += 1;
+            }
+        }
+        assert_eq!(count, 37);
+        assert_eq!(
+            split_max(65530u16..=u16::MAX),
+            (65530..u16::MAX, Some(u16::MAX))
+        );
+        assert_eq!(split_max(1u16..=10), (1..11, None));
+    }
+}
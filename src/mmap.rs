@@ -0,0 +1,123 @@
+use crate::aliquot::Generator;
+use crate::error::AliquotError;
+use crate::types::Number;
+use memmap2::{Mmap, MmapMut};
+use std::fs::{File, OpenOptions};
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::Path;
+
+impl<T: Number> Generator<T> {
+    /// Computes the aliquot sums of all numbers in the range and writes them to
+    /// a file. The sum of n is stored in little-endian order at the offset
+    /// (n - start) * size_of::<T>(), so it can be looked up using MmapSums.
+    pub fn compute_to_mmap(range: Range<T>, path: &Path) -> Result<(), AliquotError> {
+        const SEGMENT_LEN: u64 = 1 << 16;
+        let width = size_of::<T>();
+        let count: u64 = if range.end > range.start {
+            (range.end - range.start).try_into().unwrap_or(u64::MAX)
+        } else {
+            0
+        };
+        let len = count.checked_mul(width as u64).ok_or_else(|| {
+            AliquotError::OverflowError(format!("Range of {count} numbers is too large"))
+        })?;
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(len)?;
+        if len == 0 {
+            return Ok(());
+        }
+        // SAFETY: The file has just been created by us and is not modified elsewhere
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        let seg_len = T::try_from(SEGMENT_LEN).unwrap_or(T::MAX);
+        let mut start = range.start;
+        let mut pos = 0;
+        while start < range.end {
+            let end = if (range.end - start) > seg_len {
+                start + seg_len
+            } else {
+                range.end
+            };
+            for sum in Self::aliquot_sum_sieve(start..end)? {
+                mmap[pos..pos + width].copy_from_slice(&sum.to_le_bytes());
+                pos += width;
+            }
+            start = end;
+        }
+        mmap.flush()?;
+        Ok(())
+    }
+}
+
+/// Aliquot sums stored in a memory-mapped file written by compute_to_mmap.
+pub struct MmapSums<T: Number> {
+    mmap: Mmap,
+    start: T,
+}
+
+impl<T: Number> MmapSums<T> {
+    /// Opens a file containing the aliquot sums of a range starting at start.
+    pub fn open(path: &Path, start: T) -> Result<Self, AliquotError> {
+        let file = File::open(path)?;
+        // SAFETY: The file must not be modified while it is mapped
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() % size_of::<T>() != 0 {
+            let err_msg = format!(
+                "Size of {} is not a multiple of {} bytes",
+                path.display(),
+                size_of::<T>()
+            );
+            return Err(AliquotError::InvalidArg(err_msg));
+        }
+        Ok(Self { mmap, start })
+    }
+
+    /// Returns the number of stored aliquot sums.
+    pub fn len(&self) -> usize {
+        self.mmap.len() / size_of::<T>()
+    }
+
+    /// Returns true, if no aliquot sums are stored.
+    pub fn is_empty(&self) -> bool {
+        self.mmap.is_empty()
+    }
+
+    /// Returns the aliquot sum of n or None, if n is not contained in the file.
+    pub fn get(&self, n: T) -> Option<T> {
+        if n < self.start {
+            return None;
+        }
+        let ind = usize::try_from((n - self.start).try_into().ok()?).ok()?;
+        if ind >= self.len() {
+            return None;
+        }
+        let width = size_of::<T>();
+        T::from_le_bytes(&self.mmap[ind * width..(ind + 1) * width])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_to_mmap() {
+        let path = std::env::temp_dir().join(format!("aliquot-sums-{}.bin", std::process::id()));
+        Generator::<u32>::compute_to_mmap(100..1101, &path).unwrap();
+        let sums = MmapSums::<u32>::open(&path, 100).unwrap();
+        assert_eq!(sums.len(), 1001);
+        for n in 100..1101 {
+            assert_eq!(sums.get(n), Generator::<u32>::aliquot_sum(n).ok());
+        }
+        assert_eq!(sums.get(220), Some(284));
+        assert_eq!(sums.get(99), None);
+        assert_eq!(sums.get(1101), None);
+        assert!(MmapSums::<u128>::open(&path, 100).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,38 @@
+use crate::aliquot::AliquotSeq;
+use crate::types::Number;
+
+/// Bases supported for formatting the terms of a sequence.
+pub const BASES: [u32; 4] = [2, 8, 10, 16];
+
+/// Formats a term in the given base. Bases other than 2, 8 and 16 fall back to decimal.
+pub fn format_term<T: Number>(value: T, base: u32) -> String {
+    match base {
+        2 => format!("{value:b}"),
+        8 => format!("{value:o}"),
+        16 => format!("{value:x}"),
+        _ => format!("{value}"),
+    }
+}
+
+/// Formats all terms of the sequence in the given base.
+pub fn format_seq<T: Number>(aliquot_seq: &AliquotSeq<T>, base: u32) -> String {
+    aliquot_seq.seq_string_with(|t| format_term(t, base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_term() {
+        assert_eq!(format_term(220u32, 2), "11011100");
+        assert_eq!(format_term(220u32, 8), "334");
+        assert_eq!(format_term(220u32, 10), "220");
+        assert_eq!(format_term(220u32, 16), "dc");
+        assert_eq!(format_term(u128::MAX, 16), "f".repeat(32));
+        assert_eq!(format_term(0u16, 2), "0");
+        let aliquot_seq = AliquotSeq::<u64>::Convergent(vec![12, 16, 15, 9, 4, 3, 1]);
+        assert_eq!(format_seq(&aliquot_seq, 16), "[c, 10, f, 9, 4, 3, 1]");
+        assert_eq!(format_seq(&aliquot_seq, 10), aliquot_seq.seq_string());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::aliquot::Generator;
+use crate::error::AliquotError;
+use crate::stats::Stats;
+use crate::types::Number;
+use std::io::{BufRead, Write};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// Interactive shell evaluating commands line by line. The generator is kept
+/// across all commands, so its cache persists.
+pub struct Repl<T: Number> {
+    gener: Generator<T>,
+    stats: Stats,
+}
+
+impl<T: Number + FromStr<Err = ParseIntError>> Repl<T> {
+    /// Returns a new shell using the generator.
+    pub fn new(gener: Generator<T>) -> Self {
+        Self {
+            gener,
+            stats: Stats::new(),
+        }
+    }
+
+    /// Evaluates a single command and returns its output or None, if the shell
+    /// should quit.
+    pub fn eval(&mut self, line: &str) -> Result<Option<String>, AliquotError> {
+        let mut words = line.split_whitespace();
+        let cmd = match words.next() {
+            Some(cmd) => cmd,
+            None => return Ok(Some(String::new())),
+        };
+        let mut arg = || -> Result<T, AliquotError> {
+            match words.next() {
+                Some(word) => Ok(T::from_str(word)?),
+                None => Err(AliquotError::InvalidArg(format!("{cmd} needs a number"))),
+            }
+        };
+        let output = match cmd {
+            "seq" => {
+                let aliquot_seq = self.gener.aliquot_seq(arg()?);
+                self.stats.add(&aliquot_seq);
+                format!(
+                    "{}: {} {}",
+                    aliquot_seq.number(),
+                    aliquot_seq.type_str(),
+                    aliquot_seq.seq_string()
+                )
+            }
+            "sum" => {
+                let n = arg()?;
+                format!("{n} {}", Generator::<T>::aliquot_sum(n)?)
+            }
+            "class" => {
+                let n = arg()?;
+                format!("{n}: {:?}", self.gener.classify_number(n)?)
+            }
+            "stats" => {
+                let mut lines = self
+                    .stats
+                    .counts()
+                    .iter()
+                    .map(|(name, count)| format!("{name}: {count}"))
+                    .collect::<Vec<String>>();
+                lines.push(format!(
+                    "Cache: {} sequences, {} numbers",
+                    self.gener.cache().n_seq(),
+                    self.gener.cache().count()
+                ));
+                lines.join("\n")
+            }
+            "help" => "Commands: seq N, sum N, class N, stats, help, quit".to_string(),
+            "quit" | "exit" => return Ok(None),
+            _ => format!("Unknown command: {cmd}"),
+        };
+        Ok(Some(output))
+    }
+
+    /// Evaluates all lines of input and writes the results to output until the
+    /// input ends or the shell quits. Errors are written to output as well.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> Result<(), AliquotError> {
+        for line in input.lines() {
+            let result = match self.eval(&line?) {
+                Ok(Some(result)) => result,
+                Ok(None) => break,
+                Err(err) => format!("Error: {err}"),
+            };
+            if !result.is_empty() {
+                writeln!(output, "{result}")?;
+            }
+        }
+        output.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repl() {
+        let input =
+            "seq 12\nsum 220\n\nclass 6\nclass 12\nseq 220\nfoo\nsum\nseq x\nstats\nquit\nseq 1\n";
+        let mut output = vec![];
+        let mut repl = Repl::new(Generator::<u64>::new());
+        repl.run(input.as_bytes(), &mut output).unwrap();
+        let expected = [
+            "12: Convergent sequence [12, 16, 15, 9, 4, 3, 1]",
+            "220 284",
+            "6: Perfect",
+            "12: Abundant",
+            "220: Amicable number 220, 284",
+            "Unknown command: foo",
+            "Error: Invalid argument: sum needs a number",
+            "Error: Conversion error: invalid digit found in string",
+            "Perfect number: 0",
+            "Prime number: 0",
+            "Convergent sequence: 1",
+            "Amicable number: 1",
+            "Sociable number: 0",
+            "Aspiring number: 0",
+            "Convergent into cycle: 0",
+            "Unknown sequence: 0",
+            "Cache: 3 sequences, 15 numbers",
+        ];
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            expected.join("\n") + "\n"
+        );
+    }
+}
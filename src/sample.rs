@@ -0,0 +1,115 @@
+use crate::error::AliquotError;
+use crate::types::Number;
+use std::ops::Range;
+
+/// Small deterministic pseudo-random number generator (SplitMix64).
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed number smaller than bound.
+    fn below(&mut self, bound: u64) -> u64 {
+        // Reject the values of the last incomplete block to avoid a bias
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let x = self.next();
+            if x < zone {
+                return x % bound;
+            }
+        }
+    }
+}
+
+/// Picks k numbers uniformly from the range with replacement. The same seed
+/// always produces the same sample. Ranges wider than the maximum of u64 are
+/// only sampled within their first u64::MAX numbers.
+pub fn sample_range<T: Number>(range: Range<T>, k: usize, seed: u64) -> Vec<T> {
+    if range.end <= range.start {
+        return vec![];
+    }
+    let width = (range.end - range.start).try_into().unwrap_or(u64::MAX);
+    let mut rng = SplitMix64::new(seed);
+    (0..k)
+        .filter_map(|_| T::try_from(rng.below(width)).ok())
+        .map(|offset| range.start + offset)
+        .collect()
+}
+
+/// Returns all numbers of the range exactly once in a deterministic
+/// pseudo-shuffled order. The offset i of a number is mapped to (a * i + c)
+/// mod len with a coprime to len, which is a bijection over the range. The
+/// product a * i must not overflow u128, so ranges wider than the maximum of
+/// u64 are rejected.
+pub fn shuffled<T: Number>(range: Range<T>) -> Result<impl Iterator<Item = T>, AliquotError> {
+    let len = if range.end > range.start {
+        let width: u64 = (range.end - range.start).try_into().map_err(|_| {
+            let err_msg = format!("{} - {} is too wide to be shuffled", range.start, range.end);
+            AliquotError::InvalidRange(err_msg)
+        })?;
+        width as u128
+    } else {
+        0
+    };
+    // Fixed constants derived from the golden ratio spread the numbers evenly
+    let mut a = 0x9e3779b97f4a7c15 % len.max(1);
+    while len > 1 && Number::gcd(a, len) != 1 {
+        a += 1;
+    }
+    let c = 0x7f4a7c15 % len.max(1);
+    Ok((0..len)
+        .filter_map(move |i| T::from_u128((a * i + c) % len))
+        .map(move |offset| range.start + offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_range() {
+        let sample = sample_range::<u64>(1_000..1_000_000_000, 100, 42);
+        assert_eq!(sample.len(), 100);
+        assert!(sample.iter().all(|n| (1_000..1_000_000_000).contains(n)));
+        assert_eq!(sample, sample_range::<u64>(1_000..1_000_000_000, 100, 42));
+        assert_ne!(sample, sample_range::<u64>(1_000..1_000_000_000, 100, 43));
+        let sample = sample_range::<u16>(10..13, 1000, 7);
+        assert!(sample.iter().all(|n| (10..13).contains(n)));
+        assert!((10..13).all(|n| sample.contains(&n)));
+        assert_eq!(sample_range::<u32>(5..5, 10, 1), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_shuffled() {
+        for range in [1u64..1001, 0..2, 276..277, 1_000_000..1_065_536] {
+            let order = shuffled(range.clone()).unwrap().collect::<Vec<u64>>();
+            if order.len() > 2 {
+                assert!(order.windows(2).any(|w| w[0] > w[1]));
+            }
+            let mut sorted = order.clone();
+            sorted.sort();
+            assert_eq!(sorted, range.collect::<Vec<u64>>());
+        }
+        assert_eq!(
+            shuffled(10u16..20).unwrap().collect::<Vec<u16>>(),
+            shuffled(10u16..20).unwrap().collect::<Vec<u16>>()
+        );
+        assert_eq!(shuffled(5u32..5).unwrap().count(), 0);
+        assert_eq!(shuffled(u16::MAX - 3..u16::MAX).unwrap().count(), 3);
+        // Ranges wider than the maximum of u64 would lose numbers
+        assert!(shuffled(0..u64::MAX as u128).is_ok());
+        assert!(shuffled(0..u64::MAX as u128 + 1).is_err());
+    }
+}
@@ -0,0 +1,135 @@
+/// Precomputed table of the smallest prime factor for every number up to a limit.
+#[derive(Clone)]
+pub struct Sieve {
+    spf: Vec<u64>,
+}
+
+impl Sieve {
+    /// Returns a new sieve for all numbers smaller than limit.
+    pub fn new(limit: usize) -> Self {
+        let mut spf = vec![0u64; limit];
+        for i in 2..limit {
+            if spf[i] == 0 {
+                // No smaller prime divides i, so i is a prime itself
+                let p = i as u64;
+                let mut m = i;
+                while m < limit {
+                    if spf[m] == 0 {
+                        spf[m] = p;
+                    }
+                    m += i;
+                }
+            }
+        }
+        Self { spf }
+    }
+
+    /// Returns the limit of the sieve. All numbers below can be looked up.
+    pub fn limit(&self) -> u64 {
+        self.spf.len() as u64
+    }
+
+    /// Returns true, if n is covered by the sieve.
+    pub fn contains(&self, n: u64) -> bool {
+        n < self.limit()
+    }
+
+    /// Returns the smallest prime factor of n or None, if n is zero, one or
+    /// exceeds the limit of the sieve.
+    pub fn smallest_prime_factor(&self, n: u64) -> Option<u64> {
+        if n < 2 || !self.contains(n) {
+            return None;
+        }
+        Some(self.spf[n as usize])
+    }
+
+    /// Returns true, if n is a prime number. Numbers beyond the limit are never prime.
+    pub fn is_prime(&self, n: u64) -> bool {
+        self.smallest_prime_factor(n) == Some(n)
+    }
+
+    /// Returns the prime factorization of n as pairs of prime and exponent
+    /// in ascending order. The factorization of numbers beyond the limit is empty.
+    pub fn factorize(&self, n: u64) -> Vec<(u64, u32)> {
+        let mut factors: Vec<(u64, u32)> = vec![];
+        let mut rest = n;
+        while let Some(p) = self.smallest_prime_factor(rest) {
+            match factors.last_mut() {
+                Some((last, exp)) if *last == p => *exp += 1,
+                _ => factors.push((p, 1)),
+            }
+            rest /= p;
+        }
+        factors
+    }
+
+    /// Sums up all proper divisors of n using its prime factorization.
+    /// Returns None, if n exceeds the limit of the sieve.
+    pub fn aliquot_sum(&self, n: u64) -> Option<u64> {
+        if !self.contains(n) {
+            return None;
+        }
+        if n <= 1 {
+            return Some(0);
+        }
+        // The sum of all divisors is multiplicative: sigma(p^k) = 1 + p + ... + p^k
+        let sigma = self
+            .factorize(n)
+            .iter()
+            .map(|&(p, k)| (0..k).fold(1, |acc, _| acc * p + 1))
+            .product::<u64>();
+        Some(sigma - n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aliquot::Generator;
+
+    #[test]
+    fn test_is_prime() {
+        let sieve = Sieve::new(100);
+        let primes = (0..100)
+            .filter(|&n| sieve.is_prime(n))
+            .collect::<Vec<u64>>();
+        assert_eq!(
+            primes,
+            vec![
+                2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79,
+                83, 89, 97
+            ]
+        );
+        assert!(!sieve.is_prime(101));
+    }
+
+    #[test]
+    fn test_factorize() {
+        let sieve = Sieve::new(2000);
+        assert_eq!(sieve.factorize(1), vec![]);
+        assert_eq!(sieve.factorize(12), vec![(2, 2), (3, 1)]);
+        assert_eq!(sieve.factorize(97), vec![(97, 1)]);
+        assert_eq!(sieve.factorize(1024), vec![(2, 10)]);
+        assert_eq!(sieve.factorize(1980), vec![(2, 2), (3, 2), (5, 1), (11, 1)]);
+        assert_eq!(sieve.factorize(2000), vec![]);
+    }
+
+    #[test]
+    fn test_aliquot_sum() {
+        let sieve = Sieve::new(10_000);
+        assert_eq!(sieve.aliquot_sum(0), Some(0));
+        assert_eq!(sieve.aliquot_sum(1), Some(0));
+        assert_eq!(sieve.aliquot_sum(6), Some(6));
+        assert_eq!(sieve.aliquot_sum(12), Some(16));
+        assert_eq!(sieve.aliquot_sum(220), Some(284));
+        assert_eq!(sieve.aliquot_sum(284), Some(220));
+        assert_eq!(sieve.aliquot_sum(10_000), None);
+        for n in 0..10_000 {
+            assert_eq!(
+                sieve.aliquot_sum(n),
+                Generator::<u64>::aliquot_sum(n).ok(),
+                "aliquot sum of {n}"
+            );
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::aliquot::AliquotSeq;
+use crate::types::Number;
+use std::sync::{Arc, Mutex};
+
+/// Counts the types of computed aliquot sequences.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub perfect: usize,
+    pub prime: usize,
+    pub convergent: usize,
+    pub amicable: usize,
+    pub sociable: usize,
+    pub aspiring: usize,
+    pub into_cycle: usize,
+    pub unknown: usize,
+}
+
+impl Stats {
+    /// Returns new stats with all counts set to zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the type of the aliquot sequence.
+    pub fn add<T: Number>(&mut self, aliquot_seq: &AliquotSeq<T>) {
+        let count = match aliquot_seq {
+            AliquotSeq::PerfectNumber(_) => &mut self.perfect,
+            AliquotSeq::PrimeNumber(_) => &mut self.prime,
+            AliquotSeq::Convergent(_) => &mut self.convergent,
+            AliquotSeq::AmicableNumber(_) => &mut self.amicable,
+            AliquotSeq::SociableNumber(_) => &mut self.sociable,
+            AliquotSeq::AspiringNumber(_) => &mut self.aspiring,
+            AliquotSeq::IntoCycle(_, _) => &mut self.into_cycle,
+            AliquotSeq::Unknown(_, _) => &mut self.unknown,
+        };
+        *count += 1;
+    }
+
+    /// Returns the total number of counted sequences.
+    pub fn total(&self) -> usize {
+        self.counts().iter().map(|(_, count)| count).sum()
+    }
+
+    /// Returns the counts together with the name of each type.
+    pub fn counts(&self) -> [(&'static str, usize); 8] {
+        [
+            ("Perfect number", self.perfect),
+            ("Prime number", self.prime),
+            ("Convergent sequence", self.convergent),
+            ("Amicable number", self.amicable),
+            ("Sociable number", self.sociable),
+            ("Aspiring number", self.aspiring),
+            ("Convergent into cycle", self.into_cycle),
+            ("Unknown sequence", self.unknown),
+        ]
+    }
+
+    /// Returns the difference of the counts of other and self for each type.
+    pub fn diff(&self, other: &Stats) -> [(&'static str, i64); 8] {
+        let counts = self.counts();
+        let others = other.counts();
+        std::array::from_fn(|i| (counts[i].0, others[i].1 as i64 - counts[i].1 as i64))
+    }
+}
+
+/// Shared handle to stats, which are updated by a generator while it computes
+/// sequences. Clones of the handle refer to the same stats, so they can be
+/// polled from another thread during a run.
+#[derive(Clone, Debug, Default)]
+pub struct StatsHandle {
+    stats: Arc<Mutex<Stats>>,
+}
+
+impl StatsHandle {
+    /// Returns a new handle to stats with all counts set to zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the type of the aliquot sequence.
+    pub fn add<T: Number>(&self, aliquot_seq: &AliquotSeq<T>) {
+        self.stats.lock().unwrap().add(aliquot_seq);
+    }
+
+    /// Returns a copy of the current stats.
+    pub fn snapshot(&self) -> Stats {
+        self.stats.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aliquot::Generator;
+
+    #[test]
+    fn test_stats_diff() {
+        let mut gener = Generator::<u64>::new();
+        let mut stats0 = Stats::new();
+        let mut stats1 = Stats::new();
+        for n in 1..100 {
+            stats0.add(&gener.aliquot_seq(n));
+        }
+        for n in 100..200 {
+            stats1.add(&gener.aliquot_seq(n));
+        }
+        assert_eq!(stats0.total(), 99);
+        assert_eq!(stats1.total(), 100);
+        assert_eq!(stats0.perfect, 2);
+        assert_eq!(stats0.aspiring, 2);
+        assert_eq!(stats0.prime, 25);
+        assert_eq!(stats1.prime, 21);
+        let diff = stats0.diff(&stats1);
+        assert_eq!(diff[0], ("Perfect number", -2));
+        assert_eq!(diff[1], ("Prime number", -4));
+        assert_eq!(diff[7], ("Unknown sequence", -1));
+        assert_eq!(diff.iter().map(|(_, delta)| delta).sum::<i64>(), 1);
+        assert!(stats0.diff(&stats0).iter().all(|&(_, delta)| delta == 0));
+    }
+
+    #[test]
+    fn test_stats_handle() {
+        let handle = StatsHandle::new();
+        let mut gener = Generator::<u64>::with_params(1_000_000_000, 1000, 1000, false);
+        gener.set_stats_handle(Some(handle.clone()));
+        let worker = std::thread::spawn(move || {
+            for n in 1..3000 {
+                gener.aliquot_seq(n);
+            }
+        });
+        // The counts never decrease while the worker is running
+        let mut last = handle.snapshot();
+        while !worker.is_finished() {
+            let stats = handle.snapshot();
+            assert!(stats.total() >= last.total());
+            let counts = stats.counts();
+            assert!(
+                (0..counts.len()).all(|i| counts[i].1 >= last.counts()[i].1),
+                "{last:?} -> {stats:?}"
+            );
+            last = stats;
+        }
+        worker.join().unwrap();
+        let stats = handle.snapshot();
+        assert_eq!(stats.total(), 2999);
+        assert_eq!(stats.perfect, 3);
+        assert_eq!(stats.amicable, 6);
+    }
+}
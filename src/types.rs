@@ -1,15 +1,34 @@
-use std::cmp::{Eq, PartialOrd};
-use std::fmt::{Debug, Display};
+use std::cmp::{Eq, Ord};
+use std::fmt::{Binary, Debug, Display, LowerHex, Octal};
 use std::hash::Hash;
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Range, RangeInclusive, Rem, RemAssign, Sub,
+    SubAssign,
+};
 
 /// Trait with contraints for unsigned numbers used to compute aliquot sequences.
+///
+/// It is implemented for u16, u32, u64 and u128, but other types may implement
+/// it as well, if they behave like an unsigned integer of a fixed width:
+/// - The order is total and consistent with the arithmetic, so the square root
+///   of n is approximated by Newton's method using comparisons only.
+/// - Division truncates and the remainder satisfies n = (n / d) * d + n % d.
+/// - Sums are only computed after checking against MAX, so the operators may
+///   panic on overflow. Subtraction is only applied to a larger minuend.
+/// - Conversions from and to u64 and u128 are exact for all values of the type.
+///   Numbers are converted to u128 for sieves and bit operations.
+///
+/// Ranges of numbers are iterated using iter_range, so the unstable Step trait
+/// is not needed.
 pub trait Number
 where
     Self: Sized
         + Copy
         + Display
         + Debug
+        + Binary
+        + Octal
+        + LowerHex
         + Add<Output = Self>
         + AddAssign
         + Sub<Output = Self>
@@ -18,14 +37,72 @@ where
         + MulAssign
         + Div<Output = Self>
         + DivAssign
+        + Rem<Output = Self>
+        + RemAssign
         + Eq
-        + PartialOrd
-        + Hash,
+        + Ord
+        + Hash
+        + TryFrom<u64>
+        + TryInto<u64>,
 {
     const ZERO: Self;
     const ONE: Self;
     const TWO: Self;
     const MAX: Self;
+
+    /// Returns the number as bytes in little-endian order.
+    fn to_le_bytes(self) -> Vec<u8>;
+
+    /// Returns the number from bytes in little-endian order or None, if the
+    /// number of bytes doesn't match the width of the type.
+    fn from_le_bytes(bytes: &[u8]) -> Option<Self>;
+
+    /// Returns the number as a floating point number. Numbers beyond 2^53 are
+    /// rounded to the nearest representable value, so large u64 and u128
+    /// numbers lose precision.
+    fn to_f64(self) -> f64;
+
+    /// Returns the number as u128, which holds all supported types without loss.
+    fn to_u128(self) -> u128;
+
+    /// Returns the number from u128 or None, if it exceeds the maximum of the type.
+    fn from_u128(v: u128) -> Option<Self>;
+
+    /// Returns the number raised to the power of exp or None on overflow.
+    fn pow(self, exp: u32) -> Option<Self>;
+
+    /// Returns the greatest common divisor using the Euclidean algorithm.
+    fn gcd(self, other: Self) -> Self {
+        let mut a = self;
+        let mut b = other;
+        while b != Self::ZERO {
+            let rem = a % b;
+            a = b;
+            b = rem;
+        }
+        a
+    }
+}
+
+/// Returns an iterator over all numbers of the range in ascending order. Unlike
+/// iterating the range itself, this works for every implementation of Number.
+pub fn iter_range<T: Number>(range: Range<T>) -> impl Iterator<Item = T> + Clone {
+    let Range { start, end } = range;
+    std::iter::successors((start < end).then_some(start), move |&n| {
+        let next = n + T::ONE;
+        (next < end).then_some(next)
+    })
+}
+
+/// Returns an iterator over all numbers of the inclusive range in ascending
+/// order. The range may end at the maximum of T without overflowing.
+pub fn iter_range_inclusive<T: Number>(
+    range: RangeInclusive<T>,
+) -> impl Iterator<Item = T> + Clone {
+    let (start, end) = range.into_inner();
+    std::iter::successors((start <= end).then_some(start), move |&n| {
+        (n < end).then(|| n + T::ONE)
+    })
 }
 
 macro_rules! impl_number {
@@ -35,6 +112,30 @@ macro_rules! impl_number {
             const ONE: Self = 1;
             const TWO: Self = 2;
             const MAX: Self = <$Type>::MAX;
+
+            fn to_le_bytes(self) -> Vec<u8> {
+                <$Type>::to_le_bytes(self).to_vec()
+            }
+
+            fn from_le_bytes(bytes: &[u8]) -> Option<Self> {
+                bytes.try_into().ok().map(<$Type>::from_le_bytes)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+
+            fn from_u128(v: u128) -> Option<Self> {
+                Self::try_from(v).ok()
+            }
+
+            fn pow(self, exp: u32) -> Option<Self> {
+                self.checked_pow(exp)
+            }
         }
     };
 }
@@ -43,3 +144,86 @@ impl_number!(u16);
 impl_number!(u32);
 impl_number!(u64);
 impl_number!(u128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_le_bytes<T: Number>(values: &[T]) {
+        for &n in values {
+            let bytes = n.to_le_bytes();
+            assert_eq!(bytes.len(), std::mem::size_of::<T>());
+            assert_eq!(T::from_le_bytes(&bytes), Some(n));
+        }
+        // Wrong widths are rejected
+        let bytes = T::MAX.to_le_bytes();
+        assert_eq!(T::from_le_bytes(&bytes[1..]), None);
+        assert_eq!(T::from_le_bytes(&[bytes.clone(), vec![0]].concat()), None);
+    }
+
+    #[test]
+    fn test_le_bytes_all_types() {
+        test_le_bytes::<u16>(&[0, 1, 220, 0x1234, u16::MAX]);
+        test_le_bytes::<u32>(&[0, 1, 1264460, u32::MAX]);
+        test_le_bytes::<u64>(&[0, 1, 179931895322, u64::MAX]);
+        test_le_bytes::<u128>(&[0, 1, 1 << 100, u128::MAX]);
+        assert_eq!(Number::to_le_bytes(0x1234u16), vec![0x34, 0x12]);
+        assert_eq!(
+            <u32 as Number>::from_le_bytes(&[0x4c, 0x4b, 0x13, 0x00]),
+            Some(1264460)
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(Number::pow(2u32, 10), Some(1024));
+        assert_eq!(Number::pow(7u64, 0), Some(1));
+        assert_eq!(Number::pow(0u16, 0), Some(1));
+        assert_eq!(Number::pow(2u16, 15), Some(32768));
+        assert_eq!(Number::pow(2u16, 16), None);
+        assert_eq!(Number::pow(3u128, 80), Some(3u128.pow(80)));
+        assert_eq!(Number::pow(3u128, 81), None);
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(12u32.gcd(18), 6);
+        assert_eq!(18u32.gcd(12), 6);
+        assert_eq!(7u64.gcd(13), 1);
+        assert_eq!(0u16.gcd(5), 5);
+        assert_eq!(5u16.gcd(0), 5);
+        assert_eq!(0u16.gcd(0), 0);
+        assert_eq!(220u128.gcd(284), 4);
+    }
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(0u16.to_f64(), 0.0);
+        assert_eq!(u16::MAX.to_f64(), 65535.0);
+        assert_eq!(1264460u32.to_f64(), 1264460.0);
+        assert_eq!((1u64 << 53).to_f64(), 9007199254740992.0);
+        assert_eq!((1u128 << 100).to_f64(), 2f64.powi(100));
+        // Precision is lost beyond 2^53
+        assert_eq!(((1u64 << 53) + 1).to_f64(), (1u64 << 53).to_f64());
+    }
+
+    #[test]
+    fn test_iter_range_inclusive() {
+        let numbers = iter_range_inclusive(65530u16..=u16::MAX).collect::<Vec<u16>>();
+        assert_eq!(numbers, (65530..=u16::MAX).collect::<Vec<u16>>());
+        assert_eq!(iter_range_inclusive(u16::MAX..=u16::MAX).count(), 1);
+        assert_eq!(
+            iter_range_inclusive(RangeInclusive::new(5u32, 4)).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_from_u128() {
+        assert_eq!(u16::from_u128(65535), Some(u16::MAX));
+        assert_eq!(u16::from_u128(65536), None);
+        assert_eq!(u64::from_u128(1 << 63), Some(1 << 63));
+        assert_eq!(u64::from_u128(1 << 64), None);
+        assert_eq!(u128::from_u128(u128::MAX), Some(u128::MAX));
+    }
+}
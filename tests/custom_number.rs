@@ -0,0 +1,124 @@
+use aliquot::aliquot::{AliquotSeq, Generator};
+use aliquot::types::Number;
+use std::fmt::{self, Binary, Display, LowerHex, Octal};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+
+/// Newtype around u64 standing in for a custom number type of another crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Wrapped(u64);
+
+macro_rules! impl_op {
+    ($Op: ident, $op: ident, $OpAssign: ident, $op_assign: ident) => {
+        impl $Op for Wrapped {
+            type Output = Wrapped;
+
+            fn $op(self, other: Wrapped) -> Wrapped {
+                Wrapped(self.0.$op(other.0))
+            }
+        }
+
+        impl $OpAssign for Wrapped {
+            fn $op_assign(&mut self, other: Wrapped) {
+                self.0.$op_assign(other.0);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign);
+impl_op!(Sub, sub, SubAssign, sub_assign);
+impl_op!(Mul, mul, MulAssign, mul_assign);
+impl_op!(Div, div, DivAssign, div_assign);
+impl_op!(Rem, rem, RemAssign, rem_assign);
+
+macro_rules! impl_fmt {
+    ($Fmt: ident) => {
+        impl $Fmt for Wrapped {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                $Fmt::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+impl_fmt!(Display);
+impl_fmt!(Binary);
+impl_fmt!(Octal);
+impl_fmt!(LowerHex);
+
+impl From<u64> for Wrapped {
+    fn from(n: u64) -> Wrapped {
+        Wrapped(n)
+    }
+}
+
+impl From<Wrapped> for u64 {
+    fn from(n: Wrapped) -> u64 {
+        n.0
+    }
+}
+
+impl Number for Wrapped {
+    const ZERO: Self = Wrapped(0);
+    const ONE: Self = Wrapped(1);
+    const TWO: Self = Wrapped(2);
+    const MAX: Self = Wrapped(u64::MAX);
+
+    fn to_le_bytes(self) -> Vec<u8> {
+        self.0.to_le_bytes().to_vec()
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes
+            .try_into()
+            .ok()
+            .map(|b| Wrapped(u64::from_le_bytes(b)))
+    }
+
+    fn to_f64(self) -> f64 {
+        self.0 as f64
+    }
+
+    fn to_u128(self) -> u128 {
+        self.0 as u128
+    }
+
+    fn from_u128(v: u128) -> Option<Self> {
+        u64::try_from(v).ok().map(Wrapped)
+    }
+
+    fn pow(self, exp: u32) -> Option<Self> {
+        self.0.checked_pow(exp).map(Wrapped)
+    }
+}
+
+fn unwrap_seq(aliquot_seq: &AliquotSeq<Wrapped>) -> Vec<u64> {
+    aliquot_seq.seq().into_iter().map(|t| t.0).collect()
+}
+
+#[test]
+fn generator_with_custom_number() {
+    let mut gener = Generator::<Wrapped>::with_params(Wrapped(1_000_000_000), 1000, 10_000, false);
+    let mut reference = Generator::<u64>::with_params(1_000_000_000, 1000, 10_000, false);
+    for n in 1..1000 {
+        let aliquot_seq = gener.aliquot_seq(Wrapped(n));
+        let expected = reference.aliquot_seq(n);
+        assert_eq!(aliquot_seq.type_str(), expected.type_str(), "{n}");
+        assert_eq!(unwrap_seq(&aliquot_seq), expected.seq(), "{n}");
+    }
+    let aliquot_seq = gener.aliquot_seq(Wrapped(1264460));
+    assert_eq!(aliquot_seq.cycle_length(), Some(4));
+    assert_eq!(
+        Generator::<Wrapped>::aliquot_sum(Wrapped(220)),
+        Ok(Wrapped(284))
+    );
+    // Methods iterating ranges and using sieves
+    let sums = Generator::<Wrapped>::aliquot_sum_sieve(Wrapped(1)..Wrapped(100)).unwrap();
+    let expected = Generator::<u64>::aliquot_sum_sieve(1..100).unwrap();
+    assert_eq!(sums.iter().map(|s| s.0).collect::<Vec<u64>>(), expected);
+    assert_eq!(
+        gener.amicable_pairs(Wrapped(1)..Wrapped(1500)),
+        vec![(Wrapped(220), Wrapped(284)), (Wrapped(1184), Wrapped(1210))]
+    );
+    assert!(gener.is_perfect(Wrapped(8128)).unwrap());
+}
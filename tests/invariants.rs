@@ -0,0 +1,55 @@
+use aliquot::aliquot::{AliquotSeq, Generator};
+use proptest::prelude::*;
+use proptest::test_runner::{Config, RngSeed};
+
+/// Fixed seed, so every run checks the same numbers.
+const SEED: u64 = 0x616c6971756f74;
+
+fn config() -> Config {
+    Config {
+        cases: 128,
+        rng_seed: RngSeed::Fixed(SEED),
+        failure_persistence: None,
+        ..Config::default()
+    }
+}
+
+proptest! {
+    #![proptest_config(config())]
+
+    #[test]
+    fn sequences_are_valid(n in 2u32..10_000_000) {
+        // Open sequences are cut off early to keep the test fast
+        let mut gener = Generator::<u32>::with_params(1 << 28, 1000, 100_000, false);
+        let aliquot_seq = gener.aliquot_seq(n);
+        prop_assert_eq!(aliquot_seq.number(), n);
+        prop_assert_eq!(gener.verify(n, &aliquot_seq), Ok(()));
+    }
+
+    #[test]
+    fn cached_sequences_are_valid(start in 2u32..1_000_000, len in 1u32..50) {
+        // Later sequences are completed using the cache of the earlier ones
+        let mut gener = Generator::<u32>::with_params(1 << 28, 1000, 100_000, false);
+        for n in start..start + len {
+            let aliquot_seq = gener.aliquot_seq(n);
+            prop_assert_eq!(gener.verify(n, &aliquot_seq), Ok(()));
+        }
+    }
+
+    #[test]
+    fn consecutive_terms_are_aliquot_sums(n in 2u32..1_000_000) {
+        let mut gener = Generator::<u32>::with_params(1 << 28, 1000, 0, false);
+        let terms = gener.aliquot_seq(n).seq();
+        prop_assert_eq!(terms[0], n);
+        for w in terms.windows(2) {
+            prop_assert_eq!(Generator::<u32>::aliquot_sum(w[0]), Ok(w[1]));
+        }
+    }
+
+    #[test]
+    fn perfect_numbers_are_fixed_points(n in 2u32..100_000) {
+        let mut gener = Generator::<u32>::new();
+        let is_perfect = matches!(gener.aliquot_seq(n), AliquotSeq::PerfectNumber(_));
+        prop_assert_eq!(is_perfect, Generator::<u32>::aliquot_sum(n) == Ok(n));
+    }
+}